@@ -65,6 +65,26 @@ impl<T: Clone> Grid<T> {
         Some(&self.data[index])
     }
 
+    pub fn get_at_mut(&mut self, x: i32, y: i32) -> Option<&mut T> {
+        // Check if the coordinates are negative.
+        if x < 0 || y < 0 {
+            return None;
+        }
+
+        // Cast coordinates into unsigned spaced.
+        let x = x as u32;
+        let y = y as u32;
+
+        // Check if the coordinates are in-bounds.
+        if x >= self.columns || y >= self.rows {
+            return None;
+        }
+
+        // Collapse coordinates into one dimension.
+        let index = (x + y * self.columns) as usize;
+        Some(&mut self.data[index])
+    }
+
     pub fn data(&self) -> &[T] {
         &self.data
     }
@@ -0,0 +1,48 @@
+pub mod headless;
+pub mod opengl;
+#[cfg(unix)]
+pub mod terminal;
+
+use blueberry::Vec2f;
+
+use crate::{
+    event::Event,
+    graphics::{
+        colour::Colour,
+        font::Font,
+        quad::Quad,
+        viewport::Viewport,
+    },
+    terminal::buffer::Buffer,
+};
+
+pub trait Backend {
+    // Backends that are not realtime advance the engine by exactly one
+    // update per frame, regardless of how long the frame took.
+    fn is_realtime(&self) -> bool {
+        true
+    }
+
+    fn poll_event(&mut self) -> Option<Event>;
+
+    fn set_text_input(&mut self, _enabled: bool) {}
+
+    fn set_font(&mut self, font: &Font);
+
+    // The size of the console, in pixels.
+    fn set_size(&mut self, width: u32, height: u32);
+
+    fn set_window_size(&mut self, _width: u32, _height: u32) {}
+
+    // Where the console is drawn in the window.
+    fn set_viewport(&mut self, _viewport: &Viewport) {}
+
+    fn clear(&mut self, colour: Colour);
+
+    fn draw_buffer(&mut self, font: &Font, offset: Vec2f, buffer: &Buffer);
+
+    // Quads are drawn over every buffer, in order.
+    fn draw_quads(&mut self, quads: &[Quad]);
+
+    fn present(&mut self);
+}
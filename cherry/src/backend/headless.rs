@@ -0,0 +1,66 @@
+use std::collections::VecDeque;
+
+use blueberry::Vec2f;
+
+use crate::{
+    event::Event,
+    graphics::{
+        colour::Colour,
+        font::Font,
+        quad::Quad,
+    },
+    terminal::buffer::Buffer,
+};
+
+use super::Backend;
+
+pub struct Headless {
+    frames: VecDeque<Vec<Event>>,
+    events: VecDeque<Event>,
+}
+
+impl Headless {
+    pub fn new(frames: Vec<Vec<Event>>) -> Self {
+        let mut headless = Self {
+            frames: frames.into_iter().collect(),
+            events: VecDeque::new(),
+        };
+
+        headless.next_frame();
+        headless
+    }
+
+    fn next_frame(&mut self) {
+        // Each scripted frame holds the events for one update. Once the
+        // script is exhausted, ask the engine to quit.
+        if let Some(frame) = self.frames.pop_front() {
+            self.events.extend(frame);
+        } else {
+            self.events.push_back(Event::Quit);
+        }
+    }
+}
+
+impl Backend for Headless {
+    fn is_realtime(&self) -> bool {
+        false
+    }
+
+    fn poll_event(&mut self) -> Option<Event> {
+        self.events.pop_front()
+    }
+
+    fn set_font(&mut self, _font: &Font) {}
+
+    fn set_size(&mut self, _width: u32, _height: u32) {}
+
+    fn clear(&mut self, _colour: Colour) {}
+
+    fn draw_buffer(&mut self, _font: &Font, _offset: Vec2f, _buffer: &Buffer) {}
+
+    fn draw_quads(&mut self, _quads: &[Quad]) {}
+
+    fn present(&mut self) {
+        self.next_frame();
+    }
+}
@@ -0,0 +1,104 @@
+use std::time::Instant;
+
+use blueberry::Vec2f;
+
+use crate::{
+    event::Event,
+    graphics::{
+        colour::Colour,
+        font::Font,
+        opengl::renderer::Renderer,
+        quad::Quad,
+        viewport::Viewport,
+    },
+    terminal::buffer::Buffer,
+    window::Window,
+};
+
+use super::Backend;
+
+pub struct OpenGl {
+    window: Window,
+    renderer: Renderer,
+    last_present: Instant,
+}
+
+impl OpenGl {
+    pub fn new(title: &str, columns: u32, rows: u32, font: &Font) -> Self {
+        let window = Window::new(title, columns * font.width(), rows * font.height());
+        let renderer = Renderer::new(columns, rows, font);
+
+        Self {
+            window,
+            renderer,
+            last_present: Instant::now(),
+        }
+    }
+}
+
+impl Backend for OpenGl {
+    fn poll_event(&mut self) -> Option<Event> {
+        let event = self.window.poll_event();
+        if self.window.take_damaged() {
+            self.renderer.invalidate();
+        }
+
+        event
+    }
+
+    fn set_text_input(&mut self, enabled: bool) {
+        self.window.set_text_input(enabled);
+    }
+
+    fn set_font(&mut self, font: &Font) {
+        self.renderer.set_font(font);
+    }
+
+    fn set_size(&mut self, width: u32, height: u32) {
+        self.renderer.set_size(width, height);
+    }
+
+    fn set_window_size(&mut self, width: u32, height: u32) {
+        self.window.set_size(width, height);
+    }
+
+    fn set_viewport(&mut self, viewport: &Viewport) {
+        // OpenGL measures the viewport from the bottom-left of the window.
+        let (_, window_height) = self.window.size();
+        let y = window_height as i32 - viewport.y - viewport.height as i32;
+
+        self.renderer
+            .set_viewport(viewport.x, y, viewport.width, viewport.height);
+    }
+
+    fn clear(&mut self, colour: Colour) {
+        self.renderer.begin(colour);
+    }
+
+    fn draw_buffer(&mut self, font: &Font, offset: Vec2f, buffer: &Buffer) {
+        self.renderer.draw_buffer(font, offset, buffer);
+    }
+
+    fn draw_quads(&mut self, quads: &[Quad]) {
+        self.renderer.draw_quads(quads);
+    }
+
+    fn present(&mut self) {
+        // Nothing changed since the last frame, so it is still on screen.
+        // Without a swap there is no wait for vsync, so wait until the next
+        // one would have been instead of spinning. The time the frame took
+        // counts towards the wait, and the engine's frame cap counts the wait
+        // as part of the frame, so the two don't add up.
+        if self.renderer.end() {
+            self.window.swap_buffers();
+        } else {
+            let taken = self.last_present.elapsed();
+            let interval = self.window.refresh_interval();
+            if taken < interval {
+                std::thread::sleep(interval - taken);
+            }
+        }
+
+        self.last_present = Instant::now();
+    }
+}
@@ -0,0 +1,647 @@
+use std::{
+    collections::VecDeque,
+    fmt::{
+        self,
+        Write as _,
+    },
+    io::{
+        Read,
+        Write,
+    },
+};
+
+use blueberry::Vec2f;
+
+use crate::{
+    event::Event,
+    graphics::{
+        colour::Colour,
+        font::Font,
+        quad::Quad,
+        viewport::Viewport,
+    },
+    input::{
+        button::Button,
+        key::Key,
+    },
+    terminal::{
+        buffer::Buffer,
+        cp437,
+        tile::Tile,
+    },
+};
+
+use super::Backend;
+
+const ENTER: &str = "\x1b[?1049h\x1b[?25l\x1b[?1003h\x1b[?1006h\x1b[2J";
+const LEAVE: &str = "\x1b[?1006l\x1b[?1003l\x1b[?25h\x1b[0m\x1b[?1049l";
+
+pub struct Terminal {
+    original: libc::termios,
+    input: Vec<u8>,
+    events: VecDeque<Event>,
+    pressed: Vec<Key>,
+    text_input: bool,
+    mouse_position: (i32, i32),
+
+    // Screen
+    // The size of the terminal in cells, when it can be read.
+    size: Option<(u32, u32)>,
+    viewport: Option<Viewport>,
+    font_width: u32,
+    font_height: u32,
+    columns: u32,
+    rows: u32,
+    frame: Vec<Tile>,
+    screen: Vec<Option<Tile>>,
+}
+
+impl Terminal {
+    // Fails when stdin is not a terminal, such as when input is piped.
+    pub fn new() -> Result<Self, TerminalError> {
+        let original = unsafe {
+            let mut original = std::mem::zeroed();
+            if libc::tcgetattr(libc::STDIN_FILENO, &mut original) != 0 {
+                return Err(TerminalError::Attributes(std::io::Error::last_os_error()));
+            }
+
+            let mut raw = original;
+            libc::cfmakeraw(&mut raw);
+            if libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw) != 0 {
+                return Err(TerminalError::RawMode(std::io::Error::last_os_error()));
+            }
+
+            original
+        };
+
+        write_stdout(ENTER);
+
+        Ok(Self {
+            original,
+            input: Vec::new(),
+            events: VecDeque::new(),
+            pressed: Vec::new(),
+            text_input: false,
+            mouse_position: (0, 0),
+            size: None,
+            viewport: None,
+            font_width: 1,
+            font_height: 1,
+            columns: 0,
+            rows: 0,
+            frame: Vec::new(),
+            screen: Vec::new(),
+        })
+    }
+
+    fn read_input(&mut self) {
+        self.read_size();
+
+        let mut stdin = std::io::stdin();
+        let mut bytes = [0u8; 256];
+
+        loop {
+            // Only read when there is input waiting, so that we never block
+            // the frame.
+            let mut fd = libc::pollfd {
+                fd: libc::STDIN_FILENO,
+                events: libc::POLLIN,
+                revents: 0,
+            };
+
+            let ready = unsafe { libc::poll(&mut fd, 1, 0) };
+            if ready <= 0 {
+                break;
+            }
+
+            match stdin.read(&mut bytes) {
+                Ok(0) | Err(_) => break,
+                Ok(length) => self.input.extend_from_slice(&bytes[..length]),
+            }
+        }
+
+        while !self.input.is_empty() {
+            // A sequence split across reads is left for the next one.
+            let length = self.parse_input();
+            if length == 0 {
+                break;
+            }
+
+            self.input.drain(..length);
+        }
+    }
+
+    // Parses one key press or mouse report from the front of the input and
+    // returns the number of bytes consumed, or zero when the input ends
+    // part way through it.
+    fn parse_input(&mut self) -> usize {
+        let input = &self.input;
+
+        if input[0] == 0x1b && input.len() > 1 {
+            match input[1] {
+                b'[' | b'O' => return self.parse_sequence(),
+                _ => {
+                    // Escape followed by a key is how terminals report Alt.
+                    let length = self.parse_input_at(1);
+                    return length + 1;
+                }
+            }
+        }
+
+        self.parse_input_at(0)
+    }
+
+    fn parse_input_at(&mut self, start: usize) -> usize {
+        // Include the continuation bytes of a UTF-8 character.
+        let length = 1 + self.input[start + 1..]
+            .iter()
+            .take_while(|b| *b & 0xc0 == 0x80)
+            .count();
+
+        let byte = self.input[start];
+        let alt = start > 0;
+        let mut modifiers = Vec::new();
+        if alt {
+            modifiers.push(Key::LAlt);
+        }
+
+        let key = match byte {
+            // Ctrl+C no longer raises a signal in raw mode.
+            0x03 => {
+                self.events.push_back(Event::Quit);
+                return 1;
+            }
+            0x1b => Key::Escape,
+            b'\r' | b'\n' => Key::Enter,
+            b'\t' => Key::Tab,
+            0x08 | 0x7f => Key::Backspace,
+            0x01..=0x1a => {
+                modifiers.push(Key::LCtrl);
+                convert_char((byte - 1 + b'a') as char)
+            }
+            _ => {
+                let c = byte as char;
+                if c.is_ascii_uppercase() || "~!@#$%^&*()_+{}|:\"<>?".contains(c) {
+                    modifiers.push(Key::LShift);
+                }
+
+                convert_char(c)
+            }
+        };
+
+        self.press(key, &modifiers);
+
+        if self.text_input && !alt && byte >= 0x20 && byte != 0x7f {
+            let text = String::from_utf8_lossy(&self.input[start..start + length]).into_owned();
+            self.events.push_back(Event::TextInput { text });
+        }
+
+        length
+    }
+
+    fn parse_sequence(&mut self) -> usize {
+        let input = &self.input;
+
+        // Find the final byte of the control sequence.
+        let end = input[2..]
+            .iter()
+            .position(|b| (0x40..=0x7e).contains(b))
+            .map(|i| i + 2);
+
+        let end = match end {
+            Some(end) => end,
+            None => return 0,
+        };
+
+        let parameters = String::from_utf8_lossy(&input[2..end]).into_owned();
+        let last = input[end];
+
+        if parameters.starts_with('<') && (last == b'M' || last == b'm') {
+            self.mouse(&parameters[1..], last == b'M');
+            return end + 1;
+        }
+
+        let values: Vec<u32> = parameters
+            .split(';')
+            .map(|value| value.parse().unwrap_or(1))
+            .collect();
+
+        let key = match last {
+            b'A' => Key::Up,
+            b'B' => Key::Down,
+            b'C' => Key::Right,
+            b'D' => Key::Left,
+            b'H' => Key::Home,
+            b'F' => Key::End,
+            b'P' => Key::F1,
+            b'Q' => Key::F2,
+            b'R' => Key::F3,
+            b'S' => Key::F4,
+            b'~' => match values[0] {
+                1 | 7 => Key::Home,
+                2 => Key::Insert,
+                3 => Key::Delete,
+                4 | 8 => Key::End,
+                5 => Key::PageUp,
+                6 => Key::PageDown,
+                11 => Key::F1,
+                12 => Key::F2,
+                13 => Key::F3,
+                14 => Key::F4,
+                15 => Key::F5,
+                17 => Key::F6,
+                18 => Key::F7,
+                19 => Key::F8,
+                20 => Key::F9,
+                21 => Key::F10,
+                23 => Key::F11,
+                24 => Key::F12,
+                // Sequences we don't recognise are dropped.
+                _ => return end + 1,
+            },
+            _ => return end + 1,
+        };
+
+        // The second parameter encodes the modifiers as a bit set plus one.
+        let mut modifiers = Vec::new();
+        if let Some(bits) = values.get(1).map(|value| value.saturating_sub(1)) {
+            if bits & 1 != 0 {
+                modifiers.push(Key::LShift);
+            }
+            if bits & 2 != 0 {
+                modifiers.push(Key::LAlt);
+            }
+            if bits & 4 != 0 {
+                modifiers.push(Key::LCtrl);
+            }
+        }
+
+        self.press(key, &modifiers);
+
+        end + 1
+    }
+
+    fn press(&mut self, key: Key, modifiers: &[Key]) {
+        // Terminals only report key presses, so each key is released again
+        // on the following frame.
+        for key in modifiers.iter().copied().chain(std::iter::once(key)) {
+            self.events.push_back(Event::KeyDown { key, scancode: key });
+            self.pressed.push(key);
+        }
+    }
+
+    fn mouse(&mut self, parameters: &str, pressed: bool) {
+        let values: Vec<i32> = parameters
+            .split(';')
+            .filter_map(|value| value.parse().ok())
+            .collect();
+
+        if values.len() != 3 {
+            return;
+        }
+
+        let code = values[0];
+        let x = (values[1] - 1) * self.font_width as i32;
+        let y = (values[2] - 1) * self.font_height as i32;
+
+        if code & 64 != 0 {
+            let delta = if code & 1 == 0 { 1 } else { -1 };
+            self.events.push_back(Event::MouseScroll { delta });
+            return;
+        }
+
+        if code & 32 != 0 {
+            self.mouse_move(x, y);
+            return;
+        }
+
+        let button = match code & 0b1100_0011 {
+            0 => Button::Left,
+            1 => Button::Middle,
+            2 => Button::Right,
+            128 => Button::X1,
+            129 => Button::X2,
+            _ => Button::Unknown,
+        };
+
+        self.mouse_move(x, y);
+        if pressed {
+            self.events.push_back(Event::MouseButtonDown { button });
+        } else {
+            self.events.push_back(Event::MouseButtonUp { button });
+        }
+    }
+
+    // Reports the terminal's size the first time it is read and whenever
+    // it changes, such as after SIGWINCH.
+    fn read_size(&mut self) {
+        let size = terminal_size();
+        if size.is_none() || size == self.size {
+            return;
+        }
+
+        self.size = size;
+
+        // The terminal may have rewrapped what was on it, so start over.
+        write_stdout("\x1b[0m\x1b[2J");
+        for cell in self.screen.iter_mut() {
+            *cell = None;
+        }
+
+        let (columns, rows) = size.unwrap();
+        self.events.push_back(Event::Resized {
+            width: columns * self.font_width,
+            height: rows * self.font_height,
+        });
+    }
+
+    fn mouse_move(&mut self, x: i32, y: i32) {
+        // The console is drawn unscaled in the top-left corner, whatever the
+        // engine's viewport, so the position is given where the engine
+        // expects to find that console pixel.
+        let (x, y) = match self.viewport {
+            Some(viewport) => viewport.to_window(x, y),
+            None => (x, y),
+        };
+
+        // Only the cell is reported, so the mouse moves a cell at a time.
+        let (last_x, last_y) = self.mouse_position;
+        self.mouse_position = (x, y);
+
+        self.events.push_back(Event::MouseMove {
+            x,
+            y,
+            dx: x - last_x,
+            dy: y - last_y,
+        });
+    }
+}
+
+#[derive(Debug)]
+pub enum TerminalError {
+    Attributes(std::io::Error),
+    RawMode(std::io::Error),
+}
+
+impl fmt::Display for TerminalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TerminalError::Attributes(error) => {
+                write!(f, "Failed to read the terminal attributes: {}", error)
+            }
+            TerminalError::RawMode(error) => write!(f, "Failed to enable raw mode: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for TerminalError {}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        write_stdout(LEAVE);
+
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original);
+        }
+    }
+}
+
+impl Backend for Terminal {
+    fn poll_event(&mut self) -> Option<Event> {
+        if self.events.is_empty() {
+            self.read_input();
+        }
+
+        self.events.pop_front()
+    }
+
+    fn set_text_input(&mut self, enabled: bool) {
+        self.text_input = enabled;
+    }
+
+    fn set_font(&mut self, font: &Font) {
+        self.font_width = font.width();
+        self.font_height = font.height();
+    }
+
+    fn set_size(&mut self, width: u32, height: u32) {
+        self.columns = width / self.font_width;
+        self.rows = height / self.font_height;
+
+        let cells = (self.columns * self.rows) as usize;
+        self.frame = vec![Tile::default(); cells];
+        self.screen = vec![None; cells];
+    }
+
+    fn set_viewport(&mut self, viewport: &Viewport) {
+        self.viewport = Some(*viewport);
+    }
+
+    fn clear(&mut self, colour: Colour) {
+        for tile in self.frame.iter_mut() {
+            tile.glyph = ' ';
+            tile.fg = colour;
+            tile.bg = colour;
+        }
+    }
+
+    fn draw_buffer(&mut self, font: &Font, offset: Vec2f, buffer: &Buffer) {
+        // The terminal has a single cell size, so every buffer is drawn one
+        // tile per cell, whatever its font.
+        let x0 = (offset.x / self.font_width as f32).floor() as i32;
+        let y0 = (offset.y / self.font_height as f32).floor() as i32;
+
+        for r in 0..buffer.rows() {
+            for c in 0..buffer.columns() {
+                let x = x0 + c as i32;
+                let y = y0 + r as i32;
+                if x < 0 || y < 0 || x >= self.columns as i32 || y >= self.rows as i32 {
+                    continue;
+                }
+
+                let index = (x + y * self.columns as i32) as usize;
+                let mut tile = *buffer.get_at(c as i32, r as i32).unwrap();
+                tile.glyph = shown_char(font, tile.glyph);
+
+                self.frame[index] = self.frame[index].composite(&tile);
+            }
+        }
+    }
+
+    fn draw_quads(&mut self, quads: &[Quad]) {
+        // Only glyphs can be shown, in the cell nearest to where they are.
+        for quad in quads {
+            let glyph = match quad.glyph {
+                Some((font, glyph)) => shown_char(font, glyph),
+                None => continue,
+            };
+
+            let x = (quad.position.x / self.font_width as f32).round() as i32;
+            let y = (quad.position.y / self.font_height as f32).round() as i32;
+            if x < 0 || y < 0 || x >= self.columns as i32 || y >= self.rows as i32 {
+                continue;
+            }
+
+            let tile = Tile {
+                glyph,
+                fg: quad.tint,
+                bg: Colour::TRANSPARENT,
+                transparent_glyph: false,
+                transparent_bg: true,
+            };
+
+            let index = (x + y * self.columns as i32) as usize;
+            self.frame[index] = self.frame[index].composite(&tile);
+        }
+    }
+
+    fn present(&mut self) {
+        let mut output = String::new();
+        let mut colours = None;
+        let mut cursor = None;
+
+        // Cells past the edge of the terminal would wrap onto the next row.
+        let (columns, rows) = self.size.unwrap_or((self.columns, self.rows));
+
+        for (index, tile) in self.frame.iter().enumerate() {
+            // Only redraw the cells that changed since the last frame.
+            if self.screen[index] == Some(*tile) {
+                continue;
+            }
+
+            let x = index as u32 % self.columns;
+            let y = index as u32 / self.columns;
+            if x >= columns || y >= rows {
+                continue;
+            }
+
+            // Writing a character already advances the cursor along the row.
+            if cursor != Some(index) || x == 0 {
+                write!(output, "\x1b[{};{}H", y + 1, x + 1).unwrap();
+            }
+
+            if colours != Some((tile.fg, tile.bg)) {
+                write!(
+                    output,
+                    "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m",
+                    tile.fg.r, tile.fg.g, tile.fg.b, tile.bg.r, tile.bg.g, tile.bg.b
+                )
+                .unwrap();
+
+                colours = Some((tile.fg, tile.bg));
+            }
+
+            output.push(tile.glyph);
+            self.screen[index] = Some(*tile);
+            cursor = Some(index + 1);
+        }
+
+        write_stdout(&output);
+
+        for key in self.pressed.drain(..) {
+            self.events.push_back(Event::KeyUp { key, scancode: key });
+        }
+    }
+}
+
+// The character the font would draw, so that unmapped characters look the
+// same as in a window.
+fn shown_char(font: &Font, c: char) -> char {
+    if font.glyph_map().contains(c) {
+        c
+    } else {
+        cp437::to_unicode(font.glyph(c))
+    }
+}
+
+// Maps a character to the key that produces it on a US layout.
+fn convert_char(c: char) -> Key {
+    const LETTERS: [Key; 26] = [
+        Key::A,
+        Key::B,
+        Key::C,
+        Key::D,
+        Key::E,
+        Key::F,
+        Key::G,
+        Key::H,
+        Key::I,
+        Key::J,
+        Key::K,
+        Key::L,
+        Key::M,
+        Key::N,
+        Key::O,
+        Key::P,
+        Key::Q,
+        Key::R,
+        Key::S,
+        Key::T,
+        Key::U,
+        Key::V,
+        Key::W,
+        Key::X,
+        Key::Y,
+        Key::Z,
+    ];
+
+    const DIGITS: [Key; 10] = [
+        Key::Num0,
+        Key::Num1,
+        Key::Num2,
+        Key::Num3,
+        Key::Num4,
+        Key::Num5,
+        Key::Num6,
+        Key::Num7,
+        Key::Num8,
+        Key::Num9,
+    ];
+
+    match c {
+        'a'..='z' => LETTERS[(c as u8 - b'a') as usize],
+        'A'..='Z' => LETTERS[(c as u8 - b'A') as usize],
+        '0'..='9' => DIGITS[(c as u8 - b'0') as usize],
+        ')' => Key::Num0,
+        '!' => Key::Num1,
+        '@' => Key::Num2,
+        '#' => Key::Num3,
+        '$' => Key::Num4,
+        '%' => Key::Num5,
+        '^' => Key::Num6,
+        '&' => Key::Num7,
+        '*' => Key::Num8,
+        '(' => Key::Num9,
+        ' ' => Key::Space,
+        '-' | '_' => Key::Minus,
+        '=' | '+' => Key::Equals,
+        '[' | '{' => Key::LeftBracket,
+        ']' | '}' => Key::RightBracket,
+        '\\' | '|' => Key::Backslash,
+        ';' | ':' => Key::Semicolon,
+        '\'' | '"' => Key::Apostrophe,
+        '`' | '~' => Key::Grave,
+        ',' | '<' => Key::Comma,
+        '.' | '>' => Key::Period,
+        '/' | '?' => Key::Slash,
+        _ => Key::Unknown,
+    }
+}
+
+fn terminal_size() -> Option<(u32, u32)> {
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    let result = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) };
+    if result != 0 || size.ws_col == 0 || size.ws_row == 0 {
+        return None;
+    }
+
+    Some((size.ws_col as u32, size.ws_row as u32))
+}
+
+fn write_stdout(data: &str) {
+    let mut stdout = std::io::stdout();
+    stdout
+        .write_all(data.as_bytes())
+        .expect("Failed to write to the terminal.");
+    stdout.flush().expect("Failed to flush the terminal.");
+}
@@ -1,146 +1,769 @@
+use std::{
+    collections::VecDeque,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
 use blueberry::Vec2f;
 
 use crate::{
+    backend::{
+        headless::Headless,
+        opengl::OpenGl,
+        Backend,
+    },
     event::Event,
-    graphics::colour::Colour,
+    graphics::colour::{
+        BlendMode,
+        Colour,
+    },
+    graphics::font::{
+        Font,
+        GlyphMode,
+    },
+    graphics::font_registry::{
+        FontId,
+        FontRegistry,
+    },
+    graphics::glyph_map::GlyphMap,
+    graphics::quad::Quad,
+    graphics::software::Rasterizer,
     graphics::sprite::Sprite,
-    graphics::{
-        font::Font,
-        opengl::renderer::Renderer,
+    graphics::sprite_registry::{
+        SpriteId,
+        SpriteRegistry,
+    },
+    graphics::viewport::{
+        ScaleMode,
+        Viewport,
+    },
+    input::action::{
+        Binding,
+        Bindings,
     },
     input::button::Button,
+    input::gamepad::{
+        Gamepad,
+        GamepadAxis,
+        GamepadButton,
+    },
     input::key::Key,
+    input::modifiers::Modifiers,
+    input::mouse::{
+        Drag,
+        Mouse,
+    },
+    input::recording::{
+        RecordedFrame,
+        RecordedState,
+        Recording,
+    },
+    input::switches::Switches,
+    scene::{
+        Scene,
+        SceneStack,
+    },
     terminal::{
         buffer::Buffer,
+        camera::Camera,
+        console::{
+            Blit,
+            Canvas,
+            Console,
+        },
+        layer::{
+            Layer,
+            MAIN_LAYER,
+        },
+        sprite_layer::{
+            Entity,
+            EntityId,
+            EntityImage,
+            SpriteLayer,
+        },
         tile::Tile,
     },
-    window::Window,
     Cherry,
 };
 
 pub struct Engine {
-    window: Window,
+    backend: Box<dyn Backend>,
 
     // Graphics
-    font: Font,
-    renderer: Renderer,
-    buffer: Buffer,
+    fonts: FontRegistry,
+    font: FontId,
+    layers: Vec<Layer>,
+    sprites: SpriteRegistry,
+    sprite_layer: SpriteLayer,
+    target: usize,
     fg: Colour,
     bg: Colour,
+    transparent_glyph: bool,
+    transparent_bg: bool,
+    blend_mode: BlendMode,
+    camera: Option<Camera>,
+
+    // Window
+    scale_mode: ScaleMode,
+    window_size: (u32, u32),
+    viewport: Viewport,
+    resized: Option<(u32, u32)>,
 
     // Input
-    mx: i32,
-    my: i32,
+    mouse_x: i32,
+    mouse_y: i32,
     md: i32,
-    buttons_last_frame: [bool; Button::count() + 1],
-    buttons_this_frame: [bool; Button::count() + 1],
-    keys_last_frame: [bool; Key::count() + 1],
-    keys_this_frame: [bool; Key::count() + 1],
+    mouse: Mouse,
+    gamepads: Vec<Gamepad>,
+    connected: Vec<u32>,
+    disconnected: Vec<u32>,
+    bindings: Bindings,
+    recording: Option<Recording>,
+    replay: VecDeque<RecordedFrame>,
+    replay_start: Option<RecordedState>,
+    buttons: Switches,
+    keys: Switches,
+    scancodes: Switches,
+    text_input: bool,
+    text: String,
+
+    quit: bool,
+
+    // Timing
+    update_rate: u32,
+    frame_cap: Option<u32>,
+    accumulator: f32,
+    alpha: f32,
+    delta: f32,
+    elapsed: f32,
+    frame: u64,
 }
 
 impl Engine {
     pub fn new(title: &str, columns: u32, rows: u32, font: &str) -> Self {
-        let font_sprite = Sprite::load(font).expect("Failed to load font.");
+        let font = open_font(font);
+        let backend = OpenGl::new(title, columns, rows, &font);
+
+        Self::with_font(Box::new(backend), columns, rows, font)
+    }
+
+    pub fn headless(columns: u32, rows: u32, font: &str, frames: Vec<Vec<Event>>) -> Self {
+        Self::with_backend(Box::new(Headless::new(frames)), columns, rows, font)
+    }
+
+    pub fn with_backend(
+        mut backend: Box<dyn Backend>,
+        columns: u32,
+        rows: u32,
+        font: &str,
+    ) -> Self {
+        let font = open_font(font);
+
+        backend.set_font(&font);
+        backend.set_size(columns * font.width(), rows * font.height());
 
-        let font_width = font_sprite.width() / 16;
-        let font_height = font_sprite.height() / 16;
+        Self::with_font(backend, columns, rows, font)
+    }
+
+    fn with_font(backend: Box<dyn Backend>, columns: u32, rows: u32, font: Font) -> Self {
+        // The main layer is opaque and covers the whole window.
+        let mut main = Layer::new(MAIN_LAYER, columns, rows);
+        for tile in main.tiles_mut().data_mut().iter_mut() {
+            *tile = Tile::default();
+        }
 
-        let window = Window::new(title, columns * font_width, rows * font_height);
-        let font = Font::new(&font_sprite);
+        // The window starts out the size of the console.
+        let window_size = (columns * font.width(), rows * font.height());
+        let viewport = Viewport::new(ScaleMode::default(), window_size, window_size);
 
-        let renderer = Renderer::new(columns, rows);
-        let buffer = Buffer::filled(Tile::default(), columns, rows);
+        let mut fonts = FontRegistry::new();
+        let font = fonts.add(font);
 
         Self {
-            window,
+            backend,
+            fonts,
             font,
-            renderer,
-            buffer,
+            layers: vec![main],
+            sprites: SpriteRegistry::new(),
+            sprite_layer: SpriteLayer::new(),
+            target: 0,
             fg: Colour::WHITE,
             bg: Colour::BLACK,
-            mx: 0,
-            my: 0,
+            transparent_glyph: false,
+            transparent_bg: false,
+            blend_mode: BlendMode::Alpha,
+            camera: None,
+            scale_mode: ScaleMode::default(),
+            window_size,
+            viewport,
+            resized: None,
+            mouse_x: 0,
+            mouse_y: 0,
             md: 0,
-            buttons_last_frame: [false; Button::count() + 1],
-            buttons_this_frame: [false; Button::count() + 1],
-            keys_this_frame: [false; Key::count() + 1],
-            keys_last_frame: [false; Key::count() + 1],
+            mouse: Mouse::new(),
+            gamepads: Vec::new(),
+            connected: Vec::new(),
+            disconnected: Vec::new(),
+            bindings: Bindings::new(),
+            recording: None,
+            replay: VecDeque::new(),
+            replay_start: None,
+            buttons: Switches::new(Button::count() + 1),
+            keys: Switches::new(Key::count() + 1),
+            scancodes: Switches::new(Key::count() + 1),
+            text_input: false,
+            text: String::new(),
+            quit: false,
+            update_rate: 60,
+            frame_cap: None,
+            accumulator: 0.0,
+            alpha: 0.0,
+            delta: 0.0,
+            elapsed: 0.0,
+            frame: 0,
         }
     }
 
+    pub fn run_scene(&mut self, scene: Box<dyn Scene>) {
+        let mut scenes = SceneStack::new(scene);
+        self.run(&mut scenes);
+    }
+
+    pub fn quit(&mut self) {
+        self.quit = true;
+    }
+
     pub fn run(&mut self, client: &mut dyn Cherry) {
+        // Limit how much time a single frame can simulate, so that a long
+        // stall does not cause a burst of catch-up updates.
+        const MAX_DELTA: f32 = 0.25;
+
+        self.quit = false;
+
+        let mut last_frame = Instant::now();
         let mut running = true;
         while running {
+            let frame_start = Instant::now();
+
+            //----------------------------------------------------------------
+            // Gather this frame's input.
+            let step = self.step();
+            let measured = if self.backend.is_realtime() {
+                let delta = frame_start.duration_since(last_frame).as_secs_f32();
+                delta.min(MAX_DELTA)
+            } else {
+                step
+            };
+
+            last_frame = frame_start;
+            let (delta, events) = self.next_frame(measured);
+
             //----------------------------------------------------------------
-            // Update input state.
-            self.buttons_last_frame
-                .copy_from_slice(&self.buttons_this_frame);
-            self.keys_last_frame.copy_from_slice(&self.keys_this_frame);
-            self.md = 0;
+            // Update timing.
+            self.delta = delta;
+            self.elapsed += delta;
+            self.accumulator += delta;
 
             //----------------------------------------------------------------
             // Process events.
-            while let Some(event) = self.window.poll_event() {
-                match event {
-                    Event::MouseButtonDown { button } => {
-                        self.buttons_this_frame[button as usize] = true;
-                    }
-                    Event::MouseButtonUp { button } => {
-                        self.buttons_this_frame[button as usize] = false;
-                    }
-                    Event::KeyDown { key } => {
-                        self.keys_this_frame[key as usize] = true;
-                    }
-                    Event::KeyUp { key } => {
-                        self.keys_this_frame[key as usize] = false;
-                    }
-                    Event::MouseMove { x, y, .. } => {
-                        self.mx = x / 8;
-                        self.my = y / 12;
-                    }
-                    Event::MouseScroll { delta } => {
-                        self.md = delta;
-                    }
-                    Event::Quit => {
-                        running = false;
-                    }
+            for event in events {
+                if let Event::Quit = event {
+                    running = false;
+                } else {
+                    self.handle_event(event);
                 }
             }
 
+            if !running {
+                break;
+            }
+
             //----------------------------------------------------------------
-            // Callback to client.
-            client.on_update(self);
+            // Callback to client at a fixed rate.
+            while self.accumulator >= step && !self.quit {
+                client.on_update(self, step);
+                self.accumulator -= step;
+
+                // Input changes are reported to exactly one update.
+                self.buttons.end_update();
+                self.keys.end_update();
+                self.scancodes.end_update();
+                for gamepad in self.gamepads.iter_mut() {
+                    gamepad.buttons.end_update();
+                }
+                self.mouse.end_update();
+                self.text.clear();
+                self.md = 0;
+                self.resized = None;
+                self.connected.clear();
+                self.disconnected.clear();
+            }
+
+            if self.quit {
+                break;
+            }
+
+            self.alpha = self.accumulator / step;
+            client.on_render(self, self.alpha);
 
             //----------------------------------------------------------------
             // Render.
-            unsafe {
-                gl::ClearColor(0.08, 0.08, 0.08, 1.0);
-                gl::Clear(gl::COLOR_BUFFER_BIT);
+            self.prepare_glyphs();
+            self.backend.clear(Colour::new(20, 20, 20));
+            for layer in visible_layers(&self.layers) {
+                let font = self.fonts.get(layer.font().unwrap_or(self.font));
+                let offset = layer_offset(self.font(), layer);
+                self.backend.draw_buffer(font, offset, layer.tiles());
             }
+            let quads = entity_quads(
+                &self.fonts,
+                self.font,
+                &self.sprites,
+                &self.sprite_layer,
+                self.camera.as_ref(),
+            );
+            self.backend.draw_quads(&quads);
+            self.backend.present();
+            self.frame += 1;
 
-            self.renderer
-                .draw_buffer(&self.font, Vec2f::zero(), &mut self.buffer);
-            self.window.swap_buffers();
+            //----------------------------------------------------------------
+            // Limit the frame rate.
+            if let Some(frame_cap) = self.frame_cap {
+                let target = Duration::from_secs_f32(1.0 / frame_cap as f32);
+                let taken = frame_start.elapsed();
+                if taken < target {
+                    std::thread::sleep(target - taken);
+                }
+            }
+        }
+    }
+
+    fn next_frame(&mut self, measured: f32) -> (f32, Vec<Event>) {
+        let mut events = Vec::new();
+        while let Some(event) = self.backend.poll_event() {
+            events.push(event);
+        }
+
+        // A replay starts from the state the recording started from, at the
+        // same point in the frame.
+        if let Some(state) = self.replay_start.take() {
+            self.restore_state(state);
+        }
+
+        // While replaying, live input is ignored apart from the user asking
+        // to quit. Once the replay runs out, live input takes over again.
+        let (delta, events) = match self.replay.pop_front() {
+            Some(frame) => {
+                let mut replayed = frame.events;
+                let quit = events.iter().any(|event| matches!(event, Event::Quit));
+                if quit && self.backend.is_realtime() {
+                    replayed.push(Event::Quit);
+                }
+
+                (frame.delta, replayed)
+            }
+            None => (measured, events),
+        };
+
+        if self
+            .recording
+            .as_ref()
+            .is_some_and(|recording| recording.start.is_none())
+        {
+            let state = self.save_state();
+            self.recording.as_mut().unwrap().start = Some(state);
+        }
+
+        if let Some(recording) = &mut self.recording {
+            recording.frames.push(RecordedFrame {
+                index: self.frame,
+                delta,
+                events: events.clone(),
+            });
         }
+
+        (delta, events)
     }
 
+    fn save_state(&self) -> RecordedState {
+        RecordedState {
+            accumulator: self.accumulator,
+            elapsed: self.elapsed,
+            mouse_position: (self.mouse_x, self.mouse_y),
+            scroll: self.md,
+            mouse: self.mouse.clone(),
+            buttons: self.buttons.clone(),
+            keys: self.keys.clone(),
+            scancodes: self.scancodes.clone(),
+            gamepads: self.gamepads.clone(),
+            connected: self.connected.clone(),
+            disconnected: self.disconnected.clone(),
+            text_input: self.text_input,
+            text: self.text.clone(),
+            resized: self.resized,
+            window_size: self.window_size,
+            scale_mode: self.scale_mode,
+        }
+    }
+
+    fn restore_state(&mut self, state: RecordedState) {
+        self.accumulator = state.accumulator;
+        self.elapsed = state.elapsed;
+        (self.mouse_x, self.mouse_y) = state.mouse_position;
+        self.md = state.scroll;
+        self.mouse = state.mouse;
+        self.buttons = state.buttons;
+        self.keys = state.keys;
+        self.scancodes = state.scancodes;
+        self.gamepads = state.gamepads;
+        self.connected = state.connected;
+        self.disconnected = state.disconnected;
+        self.text_input = state.text_input;
+        self.text = state.text;
+        self.resized = state.resized;
+
+        self.window_size = state.window_size;
+        self.scale_mode = state.scale_mode;
+        if self.scale_mode == ScaleMode::Grow {
+            self.grow();
+        }
+
+        self.update_viewport();
+    }
+
+    fn handle_event(&mut self, event: Event) {
+        match event {
+            Event::MouseButtonDown { button } => {
+                self.buttons.press(button as usize);
+                self.mouse.press(button, self.mouse_cell(), self.elapsed);
+            }
+            Event::MouseButtonUp { button } => {
+                self.buttons.release(button as usize);
+                self.mouse.release(button);
+            }
+            Event::KeyDown { key, scancode } => {
+                self.keys.press(key as usize);
+                self.scancodes.press(scancode as usize);
+            }
+            Event::KeyUp { key, scancode } => {
+                self.keys.release(key as usize);
+                self.scancodes.release(scancode as usize);
+            }
+            Event::TextInput { text } => {
+                if self.text_input {
+                    self.text.push_str(&text);
+                }
+            }
+            Event::MouseMove { x, y, .. } => {
+                let (x, y) = self.viewport.to_console(x, y);
+                self.mouse_x = x;
+                self.mouse_y = y;
+                self.mouse.moved(self.mouse_cell());
+            }
+            Event::MouseScroll { delta } => {
+                self.md += delta;
+            }
+            Event::Resized { width, height } => {
+                self.window_size = (width, height);
+                self.resized = Some((width, height));
+                if self.scale_mode == ScaleMode::Grow {
+                    self.grow();
+                }
+
+                self.update_viewport();
+            }
+            Event::GamepadConnected { id } => {
+                if self.gamepad(id).is_none() {
+                    self.gamepads.push(Gamepad::new(id));
+                    self.connected.push(id);
+                }
+            }
+            Event::GamepadDisconnected { id } => {
+                if self.gamepad(id).is_some() {
+                    self.gamepads.retain(|gamepad| gamepad.id != id);
+                    self.disconnected.push(id);
+                }
+            }
+            Event::GamepadButtonDown { id, button } => {
+                if let Some(gamepad) = self.gamepad_mut(id) {
+                    gamepad.buttons.press(button as usize);
+                }
+            }
+            Event::GamepadButtonUp { id, button } => {
+                if let Some(gamepad) = self.gamepad_mut(id) {
+                    gamepad.buttons.release(button as usize);
+                }
+            }
+            Event::GamepadAxisMotion { id, axis, value } => {
+                if let Some(gamepad) = self.gamepad_mut(id) {
+                    gamepad.axes[axis as usize] = value;
+                }
+            }
+            Event::Quit => {}
+        }
+    }
+
+    pub fn start_recording(&mut self) {
+        self.recording = Some(Recording::new(self.update_rate));
+    }
+
+    pub fn stop_recording(&mut self) -> Option<Recording> {
+        self.recording.take()
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    pub fn replay(&mut self, recording: Recording) {
+        self.set_update_rate(recording.update_rate);
+        self.replay_start = recording.start;
+        self.replay = recording.frames.into_iter().collect();
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        !self.replay.is_empty()
+    }
+
+    pub fn set_update_rate(&mut self, update_rate: u32) {
+        assert!(update_rate > 0, "The update rate must be positive.");
+        self.update_rate = update_rate;
+    }
+
+    pub fn update_rate(&self) -> u32 {
+        self.update_rate
+    }
+
+    pub fn set_frame_cap(&mut self, frame_cap: Option<u32>) {
+        self.frame_cap = frame_cap.filter(|cap| *cap > 0);
+    }
+
+    pub fn frame_cap(&self) -> Option<u32> {
+        self.frame_cap
+    }
+
+    pub fn step(&self) -> f32 {
+        1.0 / self.update_rate as f32
+    }
+
+    pub fn delta(&self) -> f32 {
+        self.delta
+    }
+
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    pub fn alpha(&self) -> f32 {
+        self.alpha
+    }
+
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    // Replaces the engine's font and resizes the window to fit the main
+    // layer in it. Layers using the engine's font follow it.
     pub fn set_font(&mut self, path: &str) {
-        // Load font.
-        let font_sprite = Sprite::load(path).expect("Failed to load font.");
-        let font = Font::new(&font_sprite);
+        self.replace_font(open_font(path));
+    }
 
-        // Update viewport.
-        let width = self.buffer.columns() * font.width();
-        let height = self.buffer.rows() * font.height();
-        self.window.set_size(width, height);
+    pub fn set_truetype_font(&mut self, path: &str, size: f32) {
+        self.replace_font(open_truetype_font(path, size));
+    }
 
+    fn replace_font(&mut self, font: Font) {
         // Update font.
-        self.font = font;
+        self.backend.set_font(&font);
+        self.fonts.replace(self.font, font);
+
+        // A growing console refills the window with cells of the new size.
+        // Otherwise the window is resized to fit the console.
+        if self.scale_mode == ScaleMode::Grow {
+            self.grow();
+        } else {
+            let (width, height) = self.console_size();
+            self.backend.set_size(width, height);
+            self.backend.set_window_size(width, height);
+            self.window_size = (width, height);
+        }
+
+        self.update_viewport();
+    }
+
+    pub fn scale_mode(&self) -> ScaleMode {
+        self.scale_mode
+    }
+
+    pub fn set_scale_mode(&mut self, scale_mode: ScaleMode) {
+        self.scale_mode = scale_mode;
+        if scale_mode == ScaleMode::Grow {
+            self.grow();
+        }
+
+        self.update_viewport();
+    }
+
+    pub fn window_size(&self) -> (u32, u32) {
+        self.window_size
+    }
+
+    // The size of the console, in pixels.
+    pub fn console_size(&self) -> (u32, u32) {
+        let width = self.buffer().columns() * self.font().width();
+        let height = self.buffer().rows() * self.font().height();
+
+        (width, height)
+    }
+
+    pub fn viewport(&self) -> Viewport {
+        self.viewport
+    }
+
+    // The new size of the window, if it changed since the last update.
+    pub fn resized(&self) -> Option<(u32, u32)> {
+        self.resized
+    }
+
+    // Adds or removes columns and rows of the main layer to fill the window.
+    // Layers the size of the main layer, such as overlays covering the
+    // whole console, grow along with it. Other layers keep their size.
+    fn grow(&mut self) {
+        let (width, height) = self.window_size;
+        let columns = (width / self.font().width()).max(1);
+        let rows = (height / self.font().height()).max(1);
+
+        let size = (self.buffer().columns(), self.buffer().rows());
+        if (columns, rows) != size {
+            for (index, layer) in self.layers.iter_mut().enumerate() {
+                if (layer.columns(), layer.rows()) != size {
+                    continue;
+                }
+
+                // Only the main layer is opaque.
+                let fill = if index == 0 {
+                    Tile::default()
+                } else {
+                    Tile::transparent()
+                };
+
+                layer.resize(columns, rows, fill);
+            }
+        }
+
+        let (width, height) = self.console_size();
+        self.backend.set_size(width, height);
+    }
+
+    fn update_viewport(&mut self) {
+        self.viewport = Viewport::new(self.scale_mode, self.console_size(), self.window_size);
+        self.backend.set_viewport(&self.viewport);
+    }
+
+    pub fn set_glyph_map(&mut self, glyphs: GlyphMap) {
+        let font = self.fonts.get_mut(self.font);
+        font.set_glyph_map(glyphs);
+        self.backend.set_font(font);
+    }
+
+    // Adds a font for layers to use, without changing the engine's font or
+    // the size of the window.
+    pub fn add_font(&mut self, font: Font) -> FontId {
+        self.fonts.add(font)
+    }
+
+    pub fn load_font(&mut self, path: &str) -> FontId {
+        self.add_font(open_font(path))
+    }
+
+    pub fn load_truetype_font(&mut self, path: &str, size: f32) -> FontId {
+        self.add_font(open_truetype_font(path, size))
+    }
+
+    pub fn fonts(&self) -> &FontRegistry {
+        &self.fonts
+    }
+
+    pub fn fonts_mut(&mut self) -> &mut FontRegistry {
+        &mut self.fonts
+    }
+
+    pub fn font_id(&self) -> FontId {
+        self.font
     }
 
     pub fn button(&self, button: Button) -> ButtonState {
-        let last_frame = self.buttons_last_frame[button as usize];
-        let this_frame = self.buttons_this_frame[button as usize];
+        button_state(&self.buttons, button as usize)
+    }
+
+    pub fn key(&self, key: Key) -> KeyState {
+        key_state(&self.keys, key)
+    }
+
+    pub fn scancode(&self, scancode: Key) -> KeyState {
+        key_state(&self.scancodes, scancode)
+    }
+
+    pub fn gamepads(&self) -> Vec<u32> {
+        self.gamepads.iter().map(|gamepad| gamepad.id).collect()
+    }
+
+    // The gamepads connected since the last update.
+    pub fn gamepads_connected(&self) -> &[u32] {
+        &self.connected
+    }
+
+    // The gamepads disconnected since the last update.
+    pub fn gamepads_disconnected(&self) -> &[u32] {
+        &self.disconnected
+    }
+
+    pub fn gamepad_button(&self, id: u32, button: GamepadButton) -> ButtonState {
+        self.gamepad(id)
+            .map(|gamepad| button_state(&gamepad.buttons, button as usize))
+            .unwrap_or_default()
+    }
+
+    pub fn gamepad_axis(&self, id: u32, axis: GamepadAxis) -> f32 {
+        self.gamepad(id)
+            .map(|gamepad| gamepad.axes[axis as usize])
+            .unwrap_or(0.0)
+    }
+
+    fn gamepad(&self, id: u32) -> Option<&Gamepad> {
+        self.gamepads.iter().find(|gamepad| gamepad.id == id)
+    }
+
+    fn gamepad_mut(&mut self, id: u32) -> Option<&mut Gamepad> {
+        self.gamepads.iter_mut().find(|gamepad| gamepad.id == id)
+    }
+
+    pub fn modifiers(&self) -> Modifiers {
+        modifiers(&self.keys, false)
+    }
+
+    pub fn bindings(&self) -> &Bindings {
+        &self.bindings
+    }
+
+    pub fn bindings_mut(&mut self) -> &mut Bindings {
+        &mut self.bindings
+    }
+
+    pub fn set_bindings(&mut self, bindings: Bindings) {
+        self.bindings = bindings;
+    }
+
+    pub fn action(&self, action: &str) -> ButtonState {
+        let bindings = self.bindings.get(action);
+        let last_frame = bindings
+            .iter()
+            .any(|binding| self.binding_down(binding, true));
+        let this_frame = bindings
+            .iter()
+            .any(|binding| self.binding_down(binding, false));
 
         let just_down = this_frame && !last_frame;
         let just_up = !this_frame && last_frame;
@@ -153,27 +776,230 @@ impl Engine {
         }
     }
 
-    pub fn key(&self, key: Key) -> KeyState {
-        let last_frame = self.keys_last_frame[key as usize];
-        let this_frame = self.keys_this_frame[key as usize];
-
-        let just_down = !last_frame && this_frame;
-        let just_up = last_frame && !this_frame;
-        let held = (!last_frame && this_frame) || (last_frame && this_frame);
+    fn binding_down(&self, binding: &Binding, last_frame: bool) -> bool {
+        let down = |switches: &Switches, index: usize| {
+            if last_frame {
+                switches.was_down(index)
+            } else {
+                switches.is_down(index)
+            }
+        };
 
-        KeyState {
-            just_down,
-            just_up,
-            held,
+        match *binding {
+            Binding::Key {
+                key,
+                modifiers: chord,
+            } => {
+                let held = modifiers(&self.keys, last_frame).without(key);
+                down(&self.keys, key as usize) && held == chord.without(key)
+            }
+            Binding::Button { button } => down(&self.buttons, button as usize),
+            Binding::Gamepad { button } => self
+                .gamepads
+                .iter()
+                .any(|gamepad| down(&gamepad.buttons, button as usize)),
         }
     }
 
+    pub fn start_text_input(&mut self) {
+        self.text_input = true;
+        self.backend.set_text_input(true);
+    }
+
+    pub fn stop_text_input(&mut self) {
+        self.text_input = false;
+        self.text.clear();
+        self.backend.set_text_input(false);
+    }
+
+    pub fn is_text_input(&self) -> bool {
+        self.text_input
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
     pub fn scroll(&self) -> i32 {
         self.md
     }
 
+    pub fn mouse_position(&self) -> (i32, i32) {
+        (self.mouse_x, self.mouse_y)
+    }
+
+    pub fn mouse_cell(&self) -> (i32, i32) {
+        let font = self.font();
+        let x = self.mouse_x.div_euclid(font.width() as i32);
+        let y = self.mouse_y.div_euclid(font.height() as i32);
+
+        (x, y)
+    }
+
+    // The cell of the layer under the mouse, in the layer's own font.
+    pub fn layer_mouse_cell(&self, name: &str) -> Option<(i32, i32)> {
+        let layer = self.layer(name)?;
+        Some(self.mouse_cell_in(layer))
+    }
+
+    fn mouse_cell_in(&self, layer: &Layer) -> (i32, i32) {
+        let font = self.fonts.get(layer.font().unwrap_or(self.font));
+        let offset = layer_offset(self.font(), layer);
+        let x = (self.mouse_x - offset.x as i32).div_euclid(font.width() as i32);
+        let y = (self.mouse_y - offset.y as i32).div_euclid(font.height() as i32);
+
+        (x, y)
+    }
+
+    pub fn double_click(&self, button: Button) -> bool {
+        self.mouse.double_click(button)
+    }
+
+    pub fn drag(&self, button: Button) -> Option<Drag> {
+        self.mouse.drag(button)
+    }
+
+    pub fn buffer(&self) -> &Buffer {
+        self.layers[0].tiles()
+    }
+
+    pub fn add_layer(&mut self, layer: Layer) {
+        // A layer with the same name is replaced.
+        if let Some(index) = self.layer_index(layer.name()) {
+            self.layers[index] = layer;
+        } else {
+            self.layers.push(layer);
+        }
+    }
+
+    pub fn remove_layer(&mut self, name: &str) -> Option<Layer> {
+        // The main layer cannot be removed.
+        let index = self.layer_index(name).filter(|index| *index != 0)?;
+
+        if self.target == index {
+            self.target = 0;
+        } else if self.target > index {
+            self.target -= 1;
+        }
+
+        Some(self.layers.remove(index))
+    }
+
+    pub fn layer(&self, name: &str) -> Option<&Layer> {
+        self.layers.iter().find(|layer| layer.name() == name)
+    }
+
+    pub fn layer_mut(&mut self, name: &str) -> Option<&mut Layer> {
+        self.layers.iter_mut().find(|layer| layer.name() == name)
+    }
+
+    fn layer_index(&self, name: &str) -> Option<usize> {
+        self.layers.iter().position(|layer| layer.name() == name)
+    }
+
+    pub fn set_layer(&mut self, name: &str) {
+        self.target = self
+            .layer_index(name)
+            .unwrap_or_else(|| panic!("There is no layer named '{}'.", name));
+    }
+
+    pub fn reset_layer(&mut self) {
+        self.target = 0;
+    }
+
+    pub fn current_layer(&self) -> &str {
+        self.layers[self.target].name()
+    }
+
+    pub fn font(&self) -> &Font {
+        self.fonts.get(self.font)
+    }
+
+    // Rasterises the glyphs each layer needs into its font's sheet.
+    fn prepare_glyphs(&mut self) {
+        for layer in self.layers.iter() {
+            let font = self.fonts.get_mut(layer.font().unwrap_or(self.font));
+            font.prepare(layer.tiles().data().iter().map(|tile| tile.glyph));
+        }
+
+        for entity in self.sprite_layer.entities() {
+            if let EntityImage::Glyph { font, glyph } = entity.image {
+                let font = self.fonts.get_mut(font.unwrap_or(self.font));
+                font.prepare(std::iter::once(glyph));
+            }
+        }
+    }
+
+    pub fn screenshot(&mut self, path: &str) -> image::ImageResult<()> {
+        self.prepare_glyphs();
+
+        let (width, height) = self.console_size();
+
+        let mut rasterizer = Rasterizer::new(width, height);
+        for layer in visible_layers(&self.layers) {
+            let font = self.fonts.get(layer.font().unwrap_or(self.font));
+            let offset = layer_offset(self.font(), layer);
+            rasterizer.draw_buffer(font, offset, layer.tiles());
+        }
+        rasterizer.draw_quads(&entity_quads(
+            &self.fonts,
+            self.font,
+            &self.sprites,
+            &self.sprite_layer,
+            self.camera.as_ref(),
+        ));
+
+        rasterizer.save(path)
+    }
+
+    pub fn add_sprite(&mut self, sprite: Sprite) -> SpriteId {
+        self.sprites.add(sprite)
+    }
+
+    pub fn load_sprite(&mut self, path: &str) -> SpriteId {
+        let sprite =
+            Sprite::load(path).unwrap_or_else(|error| panic!("Failed to load sprite. {}", error));
+
+        self.add_sprite(sprite)
+    }
+
+    pub fn sprites(&self) -> &SpriteRegistry {
+        &self.sprites
+    }
+
+    pub fn sprite_layer(&self) -> &SpriteLayer {
+        &self.sprite_layer
+    }
+
+    pub fn sprite_layer_mut(&mut self) -> &mut SpriteLayer {
+        &mut self.sprite_layer
+    }
+
+    pub fn add_entity(&mut self, entity: Entity) -> EntityId {
+        self.sprite_layer.add(entity)
+    }
+
+    pub fn remove_entity(&mut self, id: EntityId) -> Option<Entity> {
+        self.sprite_layer.remove(id)
+    }
+
+    pub fn entity(&self, id: EntityId) -> Option<&Entity> {
+        self.sprite_layer.get(id)
+    }
+
+    pub fn entity_mut(&mut self, id: EntityId) -> Option<&mut Entity> {
+        self.sprite_layer.get_mut(id)
+    }
+
     pub fn clear(&mut self) {
-        for tile in self.buffer.data_mut().iter_mut() {
+        // Only the main layer is opaque once cleared, so that the layers
+        // beneath the others show through.
+        if self.target != 0 {
+            self.layers[self.target].clear();
+            return;
+        }
+
+        for tile in self.layers[0].tiles_mut().data_mut().iter_mut() {
             tile.glyph = ' ';
             tile.fg = Colour::WHITE;
             tile.bg = self.bg;
@@ -188,78 +1014,228 @@ impl Engine {
         self.bg = bg;
     }
 
-    pub fn draw(&mut self, x: i32, y: i32, c: char) {
-        // Check for out-of-bounds.
-        let columns = self.buffer.columns() as i32;
-        let rows = self.buffer.rows() as i32;
-        if x < 0 || x >= columns || y < 0 || y >= rows {
-            // The coordinates are out-of-bounds!
-            return;
-        }
+    pub fn set_transparent_glyph(&mut self, transparent: bool) {
+        self.transparent_glyph = transparent;
+    }
 
-        let index = (x + y * columns) as usize;
-        let tile = &mut self.buffer.get_mut(index).unwrap();
-        tile.glyph = c;
-        tile.fg = self.fg;
-        tile.bg = self.bg;
+    pub fn set_transparent_bg(&mut self, transparent: bool) {
+        self.transparent_bg = transparent;
     }
 
-    pub fn draw_h_line(&mut self, x: i32, y: i32, w: i32, c: char) {
-        let x0 = x;
-        let x1 = x + w;
+    pub fn set_blend_mode(&mut self, mode: BlendMode) {
+        self.blend_mode = mode;
+    }
+
+    pub fn camera(&self) -> Option<&Camera> {
+        self.camera.as_ref()
+    }
+
+    pub fn camera_mut(&mut self) -> Option<&mut Camera> {
+        self.camera.as_mut()
+    }
+
+    // While a camera is set, drawing is in world coordinates and clipped to
+    // the camera's viewport on the current layer.
+    pub fn set_camera(&mut self, camera: Option<Camera>) {
+        self.camera = camera;
+    }
 
-        for x in x0..x1 {
-            self.draw(x, y, c);
+    pub fn mouse_world(&self) -> Option<(i32, i32)> {
+        let camera = self.camera.as_ref()?;
+        let (x, y) = self.mouse_cell_in(&self.layers[self.target]);
+
+        camera.to_world(x, y)
+    }
+
+    fn canvas(&mut self) -> Canvas<'_> {
+        let pen = self.pen();
+        Canvas::new(
+            self.layers[self.target].tiles_mut(),
+            pen,
+            self.blend_mode,
+            self.camera.as_ref(),
+        )
+    }
+
+    fn pen(&self) -> Tile {
+        Tile {
+            glyph: ' ',
+            fg: self.fg,
+            bg: self.bg,
+            transparent_glyph: self.transparent_glyph,
+            transparent_bg: self.transparent_bg,
         }
     }
 
+    pub fn draw(&mut self, x: i32, y: i32, c: char) {
+        self.canvas().draw(x, y, c);
+    }
+
+    pub fn draw_h_line(&mut self, x: i32, y: i32, w: i32, c: char) {
+        self.canvas().draw_h_line(x, y, w, c);
+    }
+
     pub fn draw_str(&mut self, x: i32, y: i32, str: &str) {
-        for (i, c) in str.chars().enumerate() {
-            self.draw(x + i as i32, y, c);
-        }
+        self.canvas().draw_str(x, y, str);
     }
 
     pub fn draw_border(&mut self, x: i32, y: i32, w: i32, h: i32) {
-        const TL: char = 0xDA as char;
-        const TR: char = 0xBF as char;
-        const BL: char = 0xC0 as char;
-        const BR: char = 0xD9 as char;
-        const VT: char = 0xB3 as char;
-        const HT: char = 0xC4 as char;
-
-        let x0 = x;
-        let x1 = x + w - 1;
-        let y0 = y;
-        let y1 = y + h - 1;
-
-        self.draw(x0, y0, TL);
-        self.draw(x1, y0, TR);
-        self.draw(x1, y1, BR);
-        self.draw(x0, y1, BL);
-
-        for x in x0 + 1..=x1 - 1 {
-            self.draw(x, y0, HT);
-            self.draw(x, y1, HT);
-        }
+        self.canvas().draw_border(x, y, w, h);
+    }
 
-        for y in y0 + 1..=y1 - 1 {
-            self.draw(x0, y, VT);
-            self.draw(x1, y, VT);
+    pub fn fill_rect(&mut self, x: i32, y: i32, w: i32, h: i32) {
+        self.canvas().fill_rect(x, y, w, h);
+    }
+
+    // Goes through the camera, like the other drawing functions.
+    pub fn blit(&mut self, console: &Console, x: i32, y: i32, blit: &Blit) {
+        let tiles = self.layers[self.target].tiles_mut();
+        console.blit(tiles, self.camera.as_ref(), x, y, blit);
+    }
+}
+
+fn modifiers(keys: &Switches, last_frame: bool) -> Modifiers {
+    let down = |key: Key| {
+        if last_frame {
+            keys.was_down(key as usize)
+        } else {
+            keys.is_down(key as usize)
         }
+    };
+
+    Modifiers {
+        shift: down(Key::LShift) || down(Key::RShift),
+        ctrl: down(Key::LCtrl) || down(Key::RCtrl),
+        alt: down(Key::LAlt) || down(Key::RAlt),
+        gui: down(Key::LGui) || down(Key::RGui),
     }
+}
 
-    pub fn fill_rect(&mut self, x: i32, y: i32, w: i32, h: i32) {
-        let x0 = x;
-        let x1 = x + w - 1;
-        let y0 = y;
-        let y1 = y + h - 1;
-
-        for y in y0..=y1 {
-            for x in x0..=x1 {
-                self.draw(x, y, ' ');
-            }
+fn button_state(buttons: &Switches, index: usize) -> ButtonState {
+    let last_frame = buttons.was_down(index);
+    let this_frame = buttons.is_down(index);
+
+    let just_down = this_frame && !last_frame;
+    let just_up = !this_frame && last_frame;
+    let held = this_frame && last_frame;
+
+    ButtonState {
+        just_down,
+        just_up,
+        held,
+    }
+}
+
+fn key_state(keys: &Switches, key: Key) -> KeyState {
+    let last_frame = keys.was_down(key as usize);
+    let this_frame = keys.is_down(key as usize);
+
+    let just_down = !last_frame && this_frame;
+    let just_up = last_frame && !this_frame;
+    let held = this_frame;
+
+    KeyState {
+        just_down,
+        just_up,
+        held,
+    }
+}
+
+// The visible layers, from the bottom to the top. Layers with the same z
+// keep the order in which they were added.
+fn visible_layers(layers: &[Layer]) -> Vec<&Layer> {
+    let mut visible: Vec<&Layer> = layers.iter().filter(|layer| layer.is_visible()).collect();
+    visible.sort_by_key(|layer| layer.z());
+    visible
+}
+
+fn layer_offset(font: &Font, layer: &Layer) -> Vec2f {
+    let (x, y) = layer.offset();
+    Vec2f::new(
+        (x * font.width() as i32) as f32,
+        (y * font.height() as i32) as f32,
+    )
+}
+
+// The entities of the sprite layer as quads, from the bottom to the top.
+fn entity_quads<'a>(
+    fonts: &'a FontRegistry,
+    font: FontId,
+    sprites: &'a SpriteRegistry,
+    layer: &SpriteLayer,
+    camera: Option<&Camera>,
+) -> Vec<Quad<'a>> {
+    if !layer.is_visible() {
+        return Vec::new();
+    }
+
+    // Entities are positioned in cells of the engine's font.
+    let cell = fonts.get(font);
+    let cell_size = Vec2f::new(cell.width() as f32, cell.height() as f32);
+    let (mut x, mut y) = layer.offset();
+
+    // Through a camera, entities are in world cells, and those outside of
+    // its viewport are hidden like the tiles there.
+    if let Some(camera) = camera {
+        let (camera_x, camera_y) = camera.position();
+        let (viewport_x, viewport_y, _, _) = camera.viewport();
+        x += (viewport_x - camera_x) as f32;
+        y += (viewport_y - camera_y) as f32;
+    }
+
+    let mut quads = Vec::new();
+    for entity in layer.entities() {
+        let (world_x, world_y) = (entity.x.floor() as i32, entity.y.floor() as i32);
+        if camera.is_some_and(|camera| !camera.is_visible(world_x, world_y)) {
+            continue;
         }
+
+        let position = Vec2f::new(entity.x + x, entity.y + y) * cell_size;
+        let quad = match entity.image {
+            EntityImage::Glyph {
+                font: glyph_font,
+                glyph,
+            } => {
+                let font = fonts.get(glyph_font.unwrap_or(font));
+                let (gx, gy) = font.glyph_position(font.glyph(glyph));
+
+                Quad {
+                    texture: font.sprite(),
+                    revision: font.revision(),
+                    source: (gx, gy, font.width(), font.height()),
+                    position,
+                    scale: entity.scale,
+                    rotation: entity.rotation,
+                    tint: entity.tint,
+                    mask: font.mode() == GlyphMode::Mask,
+                    glyph: Some((font, glyph)),
+                }
+            }
+            EntityImage::Region { sprite, x, y, w, h } => Quad {
+                texture: sprites.get(sprite),
+                revision: 0,
+                source: (x, y, w, h),
+                position,
+                scale: entity.scale,
+                rotation: entity.rotation,
+                tint: entity.tint,
+                mask: false,
+                glyph: None,
+            },
+        };
+
+        quads.push(quad);
     }
+
+    quads
+}
+
+fn open_font(path: &str) -> Font {
+    Font::load(path).unwrap_or_else(|error| panic!("Failed to load font. {}", error))
+}
+
+fn open_truetype_font(path: &str, size: f32) -> Font {
+    Font::load_truetype(path, size).unwrap_or_else(|error| panic!("Failed to load font. {}", error))
 }
 
 #[derive(Debug, Default, Copy, Clone)]
@@ -1,14 +1,68 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
 use crate::input::{
     button::Button,
+    gamepad::{
+        GamepadAxis,
+        GamepadButton,
+    },
     key::Key,
 };
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Event {
-    KeyDown { key: Key },
-    KeyUp { key: Key },
-    MouseMove { x: i32, y: i32, dx: i32, dy: i32 },
-    MouseButtonDown { button: Button },
-    MouseButtonUp { button: Button },
-    MouseScroll { delta: i32 },
+    KeyDown {
+        key: Key,
+        scancode: Key,
+    },
+    KeyUp {
+        key: Key,
+        scancode: Key,
+    },
+    TextInput {
+        text: String,
+    },
+    MouseMove {
+        x: i32,
+        y: i32,
+        dx: i32,
+        dy: i32,
+    },
+    MouseButtonDown {
+        button: Button,
+    },
+    MouseButtonUp {
+        button: Button,
+    },
+    MouseScroll {
+        delta: i32,
+    },
+    // The new size of the window, in pixels.
+    Resized {
+        width: u32,
+        height: u32,
+    },
+    GamepadConnected {
+        id: u32,
+    },
+    GamepadDisconnected {
+        id: u32,
+    },
+    GamepadButtonDown {
+        id: u32,
+        button: GamepadButton,
+    },
+    GamepadButtonUp {
+        id: u32,
+        button: GamepadButton,
+    },
+    GamepadAxisMotion {
+        id: u32,
+        axis: GamepadAxis,
+        value: f32,
+    },
     Quit,
 }
@@ -1,4 +1,13 @@
 pub mod colour;
 pub mod font;
+pub mod font_registry;
+pub mod glyph_map;
+pub mod gradient;
 pub mod opengl;
+pub mod palette;
+pub mod quad;
+pub mod software;
 pub mod sprite;
+pub mod sprite_registry;
+pub mod truetype;
+pub mod viewport;
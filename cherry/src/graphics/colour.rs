@@ -1,15 +1,235 @@
-#[derive(Debug, Copy, Clone)]
+// The layout matches the pixels uploaded to textures.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Colour {
     pub r: u8,
     pub g: u8,
     pub b: u8,
+    pub a: u8,
 }
 
 impl Colour {
     pub const fn new(r: u8, g: u8, b: u8) -> Self {
-        Self { r, g, b }
+        Self::rgba(r, g, b, 255)
     }
 
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+
+    pub const fn with_alpha(self, a: u8) -> Self {
+        Self { a, ..self }
+    }
+
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let channel = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+
+        Self::rgba(
+            channel(self.r, other.r),
+            channel(self.g, other.g),
+            channel(self.b, other.b),
+            channel(self.a, other.a),
+        )
+    }
+
+    // Interpolates through a perceptual colour space (Oklab), so that the
+    // midpoints keep an even brightness.
+    pub fn lerp_perceptual(self, other: Self, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let a = self.to_oklab();
+        let b = other.to_oklab();
+
+        let lab = [
+            a[0] + (b[0] - a[0]) * t,
+            a[1] + (b[1] - a[1]) * t,
+            a[2] + (b[2] - a[2]) * t,
+        ];
+
+        let alpha = self.a as f32 + (other.a as f32 - self.a as f32) * t;
+        Self::from_oklab(lab).with_alpha(alpha.round() as u8)
+    }
+
+    // Hue is in degrees, saturation and value are between 0 and 1.
+    pub fn to_hsv(self) -> (f32, f32, f32) {
+        let (r, g, b) = self.normalised();
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let chroma = max - min;
+
+        let saturation = if max > 0.0 { chroma / max } else { 0.0 };
+        (hue(r, g, b, max, chroma), saturation, max)
+    }
+
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Self {
+        let saturation = saturation.clamp(0.0, 1.0);
+        let value = value.clamp(0.0, 1.0);
+
+        let chroma = value * saturation;
+        from_hue(hue, chroma, value - chroma)
+    }
+
+    // Hue is in degrees, saturation and lightness are between 0 and 1.
+    pub fn to_hsl(self) -> (f32, f32, f32) {
+        let (r, g, b) = self.normalised();
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let chroma = max - min;
+
+        let lightness = (max + min) / 2.0;
+        let saturation = if lightness > 0.0 && lightness < 1.0 {
+            chroma / (1.0 - (2.0 * lightness - 1.0).abs())
+        } else {
+            0.0
+        };
+
+        (hue(r, g, b, max, chroma), saturation, lightness)
+    }
+
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32) -> Self {
+        let saturation = saturation.clamp(0.0, 1.0);
+        let lightness = lightness.clamp(0.0, 1.0);
+
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        from_hue(hue, chroma, lightness - chroma / 2.0)
+    }
+
+    // Accepts `#rgb`, `#rrggbb` and `#rrggbbaa`, with or without the `#`.
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.trim();
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if !hex.is_ascii() {
+            return None;
+        }
+
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+
+        match hex.len() {
+            3 => {
+                let short = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).ok().map(|v| v * 17);
+                Some(Self::new(short(0)?, short(1)?, short(2)?))
+            }
+            6 => Some(Self::new(channel(0)?, channel(2)?, channel(4)?)),
+            8 => Some(Self::rgba(
+                channel(0)?,
+                channel(2)?,
+                channel(4)?,
+                channel(6)?,
+            )),
+            _ => None,
+        }
+    }
+
+    // The alpha is only written when the colour is not opaque.
+    pub fn to_hex(self) -> String {
+        if self.a == 255 {
+            format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+        } else {
+            format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
+        }
+    }
+
+    // Scales the value of the colour. A factor above 1 brightens it.
+    pub fn adjust_brightness(self, factor: f32) -> Self {
+        let (hue, saturation, value) = self.to_hsv();
+        Self::from_hsv(hue, saturation, value * factor).with_alpha(self.a)
+    }
+
+    // Scales the saturation of the colour. A factor of 0 makes it grey.
+    pub fn adjust_saturation(self, factor: f32) -> Self {
+        let (hue, saturation, value) = self.to_hsv();
+        Self::from_hsv(hue, saturation * factor, value).with_alpha(self.a)
+    }
+
+    // The distance between two colours as they are perceived.
+    pub fn distance(self, other: Self) -> f32 {
+        let a = self.to_oklab();
+        let b = other.to_oklab();
+
+        ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+    }
+
+    fn normalised(self) -> (f32, f32, f32) {
+        (
+            self.r as f32 / 255.0,
+            self.g as f32 / 255.0,
+            self.b as f32 / 255.0,
+        )
+    }
+
+    fn to_oklab(self) -> [f32; 3] {
+        let (r, g, b) = self.normalised();
+        let (r, g, b) = (to_linear(r), to_linear(g), to_linear(b));
+
+        let l = (0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+        let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+        let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+
+        [
+            0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+            1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+            0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+        ]
+    }
+
+    fn from_oklab(lab: [f32; 3]) -> Self {
+        let l = (lab[0] + 0.396_337_78 * lab[1] + 0.215_803_76 * lab[2]).powi(3);
+        let m = (lab[0] - 0.105_561_346 * lab[1] - 0.063_854_17 * lab[2]).powi(3);
+        let s = (lab[0] - 0.089_484_18 * lab[1] - 1.291_485_5 * lab[2]).powi(3);
+
+        let r = 4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s;
+        let g = -1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s;
+        let b = -0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s;
+
+        Self::new(from_linear(r), from_linear(g), from_linear(b))
+    }
+
+    // Places `above` over this colour, combining the two with the blend
+    // mode where they overlap.
+    pub fn blend(self, above: Self, mode: BlendMode) -> Self {
+        let below_alpha = self.a as f32 / 255.0;
+        let above_alpha = above.a as f32 / 255.0;
+        let alpha = above_alpha + below_alpha * (1.0 - above_alpha);
+        if alpha <= 0.0 {
+            return Self::TRANSPARENT;
+        }
+
+        let channel = |below: u8, above: u8| {
+            let below = below as f32 / 255.0;
+            let above = above as f32 / 255.0;
+
+            let blended = match mode {
+                BlendMode::Alpha => above,
+                BlendMode::Add => (below + above).min(1.0),
+                BlendMode::Multiply => below * above,
+                BlendMode::Screen => below + above - below * above,
+                BlendMode::Overlay => {
+                    if below <= 0.5 {
+                        2.0 * below * above
+                    } else {
+                        1.0 - 2.0 * (1.0 - below) * (1.0 - above)
+                    }
+                }
+                BlendMode::Lighten => below.max(above),
+                BlendMode::Darken => below.min(above),
+            };
+
+            // Where there is nothing below, the colour above is unchanged.
+            let mixed = (1.0 - below_alpha) * above + below_alpha * blended;
+            let value = above_alpha * mixed + below_alpha * below * (1.0 - above_alpha);
+
+            (value / alpha * 255.0).round() as u8
+        };
+
+        Self::rgba(
+            channel(self.r, above.r),
+            channel(self.g, above.g),
+            channel(self.b, above.b),
+            (alpha * 255.0).round() as u8,
+        )
+    }
+
+    pub const TRANSPARENT: Colour = Colour::rgba(0, 0, 0, 0);
     pub const GRAY: Colour = Colour::new(192, 192, 192);
     pub const DARK_GRAY: Colour = Colour::new(128, 128, 128);
     pub const VERY_DARK_GRAY: Colour = Colour::new(64, 64, 64);
@@ -34,3 +254,70 @@ impl Colour {
     pub const BLACK: Colour = Colour::new(0, 0, 0);
     pub const WHITE: Colour = Colour::new(255, 255, 255);
 }
+
+// The hue in degrees of a normalised colour.
+fn hue(r: f32, g: f32, b: f32, max: f32, chroma: f32) -> f32 {
+    if chroma <= 0.0 {
+        return 0.0;
+    }
+
+    let hue = if max == r {
+        ((g - b) / chroma).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / chroma + 2.0
+    } else {
+        (r - g) / chroma + 4.0
+    };
+
+    hue * 60.0
+}
+
+// Builds a colour from its hue, chroma and the amount added to every
+// channel.
+fn from_hue(hue: f32, chroma: f32, offset: f32) -> Colour {
+    let sector = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    let channel = |value: f32| ((value + offset) * 255.0).round().clamp(0.0, 255.0) as u8;
+    Colour::new(channel(r), channel(g), channel(b))
+}
+
+fn to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn from_linear(value: f32) -> u8 {
+    let value = value.clamp(0.0, 1.0);
+    let value = if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    };
+
+    (value * 255.0).round() as u8
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum BlendMode {
+    #[default]
+    Alpha,
+    Add,
+    Multiply,
+    Screen,
+    Overlay,
+    Lighten,
+    Darken,
+}
@@ -1,25 +1,242 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    fs::File,
+    io::BufReader,
+    path::Path,
+};
+
+use serde::Deserialize;
+
 use super::{
-    opengl::texture::Texture,
+    glyph_map::{
+        GlyphMap,
+        GlyphMapError,
+    },
     sprite::Sprite,
+    truetype::TrueType,
 };
 
+#[derive(Debug, Default, Copy, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GlyphMode {
+    // The brightness of the glyph chooses between the tile's foreground and
+    // background colours.
+    #[default]
+    Mask,
+    // The glyph keeps its own colours, and its alpha shows the background.
+    Colour,
+}
+
 pub struct Font {
     width: u32,
     height: u32,
-    texture: Texture,
+    columns: u32,
+    rows: u32,
+    padding: u32,
+    spacing: u32,
+    mode: GlyphMode,
+    sprite: Sprite,
+    glyphs: GlyphMap,
+    truetype: Option<TrueType>,
+    used: u32,
+    revision: u32,
+}
+
+#[derive(Deserialize)]
+struct Descriptor {
+    image: String,
+    glyph_width: u32,
+    glyph_height: u32,
+    #[serde(default = "default_sheet_size")]
+    columns: u32,
+    #[serde(default = "default_sheet_size")]
+    rows: u32,
+    #[serde(default)]
+    padding: u32,
+    #[serde(default)]
+    spacing: u32,
+    #[serde(default)]
+    mode: GlyphMode,
+    #[serde(default)]
+    fallback: Option<u32>,
+    #[serde(default)]
+    glyphs: Option<BTreeMap<String, u32>>,
+}
+
+fn default_sheet_size() -> u32 {
+    16
 }
 
 impl Font {
-    pub fn new(sprite: &Sprite) -> Self {
+    // A code page 437 sheet of 16 by 16 glyphs with no padding or spacing.
+    pub fn new(sprite: Sprite) -> Self {
         let width = sprite.width() / 16;
         let height = sprite.height() / 16;
-        let texture = Texture::new(sprite.width(), sprite.height(), sprite.data());
 
         Self {
             width,
             height,
-            texture,
+            columns: 16,
+            rows: 16,
+            padding: 0,
+            spacing: 0,
+            mode: GlyphMode::Mask,
+            sprite,
+            glyphs: GlyphMap::cp437(),
+            truetype: None,
+            used: 256,
+            revision: 0,
+        }
+    }
+
+    // Loads a TrueType or OpenType font at a size in pixels. Glyphs are
+    // drawn into the font's sheet the first time they are needed, one per
+    // cell of the sheet.
+    pub fn load_truetype(path: &str, size: f32) -> Result<Self, FontError> {
+        const COLUMNS: u32 = 32;
+        const ROWS: u32 = 8;
+
+        let truetype = TrueType::load(path, size)?;
+        let (width, height) = truetype.cell_size();
+
+        // Sizes share a file, so the sheet is named after both.
+        let name = format!("{}@{}", path, size);
+        let sprite = Sprite::new(&name, COLUMNS * width, ROWS * height);
+
+        // The first glyph is left blank for spaces.
+        let mut glyphs = GlyphMap::new(0);
+        glyphs.insert(' ', 0);
+
+        let mut font = Self {
+            width,
+            height,
+            columns: COLUMNS,
+            rows: ROWS,
+            padding: 0,
+            spacing: 0,
+            mode: GlyphMode::Mask,
+            sprite,
+            glyphs,
+            truetype: Some(truetype),
+            used: 1,
+            revision: 0,
+        };
+
+        // Characters the font does not have are drawn as the replacement
+        // character, or a question mark.
+        let fallback = ['\u{FFFD}', '?']
+            .iter()
+            .copied()
+            .find(|c| font.truetype.as_ref().unwrap().has_glyph(*c));
+        if let Some(fallback) = fallback {
+            font.prepare(std::iter::once(fallback));
+            let glyph = font.glyphs.glyph(fallback);
+            font.glyphs.set_fallback(glyph);
+        }
+
+        Ok(font)
+    }
+
+    // Loads either a JSON font descriptor or an image of a code page 437
+    // sheet.
+    pub fn load(path: &str) -> Result<Self, FontError> {
+        let is_descriptor = Path::new(path)
+            .extension()
+            .map(|extension| extension.eq_ignore_ascii_case("json"))
+            .unwrap_or(false);
+
+        if !is_descriptor {
+            return Ok(Self::new(Sprite::load(path)?));
         }
+
+        let file = File::open(path)?;
+        let descriptor: Descriptor = serde_json::from_reader(BufReader::new(file))?;
+
+        // The image is found relative to the descriptor.
+        let image = Path::new(path)
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .join(&descriptor.image);
+        let sprite = Sprite::load(&image.to_string_lossy())?;
+
+        let custom = descriptor.glyphs.is_some();
+        let glyphs = match descriptor.glyphs {
+            Some(glyphs) => GlyphMap::from_table(descriptor.fallback, glyphs)?,
+            None => {
+                let mut glyphs = GlyphMap::cp437();
+                if let Some(fallback) = descriptor.fallback {
+                    glyphs.set_fallback(fallback);
+                }
+
+                glyphs
+            }
+        };
+
+        let font = Self {
+            width: descriptor.glyph_width,
+            height: descriptor.glyph_height,
+            columns: descriptor.columns,
+            rows: descriptor.rows,
+            padding: descriptor.padding,
+            spacing: descriptor.spacing,
+            mode: descriptor.mode,
+            sprite,
+            glyphs,
+            truetype: None,
+            used: descriptor.columns * descriptor.rows,
+            revision: 0,
+        };
+
+        font.validate(custom)?;
+        Ok(font)
+    }
+
+    fn validate(&self, custom: bool) -> Result<(), FontError> {
+        if self.width == 0 || self.height == 0 || self.columns == 0 || self.rows == 0 {
+            return Err(FontError::Mismatch(String::from(
+                "The glyph size and sheet size must be positive",
+            )));
+        }
+
+        let (width, height) = self.sheet_size();
+        if width > self.sprite.width() || height > self.sprite.height() {
+            return Err(FontError::Mismatch(format!(
+                "The sheet needs an image of at least {}x{}, but '{}' is {}x{}",
+                width,
+                height,
+                self.sprite.path(),
+                self.sprite.width(),
+                self.sprite.height()
+            )));
+        }
+
+        // The built-in table falls back for glyphs beyond a smaller sheet,
+        // but a custom table must fit the sheet.
+        let max_glyph = if custom {
+            self.glyphs.max_glyph()
+        } else {
+            self.glyphs.fallback()
+        };
+
+        if max_glyph >= self.glyph_count() {
+            return Err(FontError::Mismatch(format!(
+                "The glyph table refers to glyph {}, but the sheet has {} glyphs",
+                max_glyph,
+                self.glyph_count()
+            )));
+        }
+
+        Ok(())
+    }
+
+    // The size of the image covered by the glyphs, padding and spacing.
+    fn sheet_size(&self) -> (u32, u32) {
+        let width =
+            self.padding * 2 + self.columns * self.width + (self.columns - 1) * self.spacing;
+        let height = self.padding * 2 + self.rows * self.height + (self.rows - 1) * self.spacing;
+
+        (width, height)
     }
 
     pub fn width(&self) -> u32 {
@@ -30,7 +247,158 @@ impl Font {
         self.height
     }
 
-    pub fn texture(&self) -> &Texture {
-        &self.texture
+    // The number of glyphs along each row of the sheet.
+    pub fn columns(&self) -> u32 {
+        self.columns
+    }
+
+    // The number of rows of glyphs in the sheet.
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    // The space around the edge of the sheet.
+    pub fn padding(&self) -> u32 {
+        self.padding
+    }
+
+    // The space between neighbouring glyphs.
+    pub fn spacing(&self) -> u32 {
+        self.spacing
+    }
+
+    pub fn mode(&self) -> GlyphMode {
+        self.mode
+    }
+
+    pub fn glyph_count(&self) -> u32 {
+        self.columns * self.rows
+    }
+
+    pub fn sprite(&self) -> &Sprite {
+        &self.sprite
+    }
+
+    // Increases whenever glyphs are added to the sheet.
+    pub fn revision(&self) -> u32 {
+        self.revision
+    }
+
+    // Draws any of the characters that a TrueType font has not drawn yet.
+    // Bitmap fonts already hold all of their glyphs.
+    pub fn prepare(&mut self, chars: impl Iterator<Item = char>) {
+        let truetype = match &self.truetype {
+            Some(truetype) => truetype,
+            None => return,
+        };
+
+        for c in chars {
+            if self.glyphs.contains(c) {
+                continue;
+            }
+
+            if !truetype.has_glyph(c) {
+                self.glyphs.insert(c, self.glyphs.fallback());
+                continue;
+            }
+
+            // Add rows to the sheet once it is full. The glyphs already in
+            // the sheet keep their place.
+            if self.used == self.columns * self.rows {
+                self.rows *= 2;
+                self.sprite
+                    .resize(self.columns * self.width, self.rows * self.height);
+            }
+
+            let glyph = self.used;
+            self.used += 1;
+
+            let (x, y) = self.glyph_position(glyph);
+            let cell = (x as i32, y as i32, self.width, self.height);
+            truetype.rasterize(c, &mut self.sprite, cell);
+
+            self.glyphs.insert(c, glyph);
+            self.revision += 1;
+        }
+    }
+
+    pub fn glyph_map(&self) -> &GlyphMap {
+        &self.glyphs
+    }
+
+    pub fn set_glyph_map(&mut self, glyphs: GlyphMap) {
+        self.glyphs = glyphs;
+    }
+
+    // The index of the glyph in the sheet that draws the character.
+    pub fn glyph(&self, c: char) -> u32 {
+        let glyph = self.glyphs.glyph(c);
+        if glyph < self.glyph_count() {
+            glyph
+        } else {
+            self.glyphs.fallback()
+        }
+    }
+
+    // The top-left pixel of the glyph in the sheet.
+    pub fn glyph_position(&self, glyph: u32) -> (u32, u32) {
+        let column = glyph % self.columns;
+        let row = glyph / self.columns;
+
+        let x = self.padding + column * (self.width + self.spacing);
+        let y = self.padding + row * (self.height + self.spacing);
+
+        (x, y)
+    }
+}
+
+#[derive(Debug)]
+pub enum FontError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Image(image::ImageError),
+    GlyphMap(GlyphMapError),
+    TrueType(String),
+    Mismatch(String),
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FontError::Io(error) => write!(f, "Failed to access font: {}", error),
+            FontError::Json(error) => write!(f, "Failed to parse font: {}", error),
+            FontError::Image(error) => write!(f, "Failed to load font image: {}", error),
+            FontError::GlyphMap(error) => write!(f, "Failed to load font glyphs: {}", error),
+            FontError::TrueType(error) => write!(f, "Failed to parse font: {}", error),
+            FontError::Mismatch(error) => {
+                write!(f, "Font does not match its image: {}", error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FontError {}
+
+impl From<std::io::Error> for FontError {
+    fn from(error: std::io::Error) -> Self {
+        FontError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for FontError {
+    fn from(error: serde_json::Error) -> Self {
+        FontError::Json(error)
+    }
+}
+
+impl From<image::ImageError> for FontError {
+    fn from(error: image::ImageError) -> Self {
+        FontError::Image(error)
+    }
+}
+
+impl From<GlyphMapError> for FontError {
+    fn from(error: GlyphMapError) -> Self {
+        FontError::GlyphMap(error)
     }
 }
@@ -0,0 +1,43 @@
+use super::font::Font;
+
+// A handle to a font held by a registry.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct FontId(usize);
+
+#[derive(Default)]
+pub struct FontRegistry {
+    fonts: Vec<Font>,
+}
+
+impl FontRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, font: Font) -> FontId {
+        self.fonts.push(font);
+        FontId(self.fonts.len() - 1)
+    }
+
+    // Fonts are never removed, so that handles stay valid. A font can be
+    // replaced instead, and everything using its handle follows it.
+    pub fn replace(&mut self, id: FontId, font: Font) -> Font {
+        std::mem::replace(&mut self.fonts[id.0], font)
+    }
+
+    pub fn get(&self, id: FontId) -> &Font {
+        &self.fonts[id.0]
+    }
+
+    pub fn get_mut(&mut self, id: FontId) -> &mut Font {
+        &mut self.fonts[id.0]
+    }
+
+    pub fn len(&self) -> usize {
+        self.fonts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fonts.is_empty()
+    }
+}
@@ -0,0 +1,155 @@
+use std::{
+    collections::{
+        BTreeMap,
+        HashMap,
+    },
+    fmt,
+    fs::File,
+    io::BufReader,
+};
+
+use serde::Deserialize;
+
+use crate::terminal::cp437;
+
+// Maps characters onto the glyphs of a font sheet, numbered from the
+// top-left of the sheet along each row.
+#[derive(Debug, Clone)]
+pub struct GlyphMap {
+    glyphs: HashMap<char, u32>,
+    fallback: u32,
+    legacy: bool,
+}
+
+#[derive(Deserialize)]
+struct GlyphMapFile {
+    #[serde(default)]
+    fallback: Option<u32>,
+    glyphs: BTreeMap<String, u32>,
+}
+
+impl GlyphMap {
+    pub fn new(fallback: u32) -> Self {
+        Self {
+            glyphs: HashMap::new(),
+            fallback,
+            legacy: false,
+        }
+    }
+
+    // The map for a code page 437 sheet. Characters outside of the code
+    // page below 256 still index the sheet directly, as byte values did
+    // before glyphs were mapped.
+    pub fn cp437() -> Self {
+        let mut map = Self::new('?' as u32);
+        map.legacy = true;
+
+        // Glyph 0 is usually blank, so the space maps onto glyph 32.
+        for (index, c) in cp437::TABLE.iter().enumerate().skip(1) {
+            map.glyphs.entry(*c).or_insert(index as u32);
+        }
+
+        map
+    }
+
+    // Loads a JSON file of the form `{ "fallback": 63, "glyphs": { "A": 65 } }`.
+    pub fn load(path: &str) -> Result<Self, GlyphMapError> {
+        let file = File::open(path)?;
+        let contents: GlyphMapFile = serde_json::from_reader(BufReader::new(file))?;
+
+        Self::from_table(contents.fallback, contents.glyphs)
+    }
+
+    pub(crate) fn from_table(
+        fallback: Option<u32>,
+        glyphs: BTreeMap<String, u32>,
+    ) -> Result<Self, GlyphMapError> {
+        let mut map = Self::new(fallback.unwrap_or('?' as u32));
+        for (key, index) in glyphs {
+            let mut chars = key.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => map.insert(c, index),
+                _ => return Err(GlyphMapError::Key(key)),
+            }
+        }
+
+        Ok(map)
+    }
+
+    // The highest glyph the map refers to.
+    pub fn max_glyph(&self) -> u32 {
+        self.glyphs
+            .values()
+            .copied()
+            .chain(std::iter::once(self.fallback))
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn insert(&mut self, c: char, glyph: u32) {
+        self.glyphs.insert(c, glyph);
+    }
+
+    pub fn contains(&self, c: char) -> bool {
+        self.glyphs.contains_key(&c)
+    }
+
+    pub fn fallback(&self) -> u32 {
+        self.fallback
+    }
+
+    pub fn set_fallback(&mut self, fallback: u32) {
+        self.fallback = fallback;
+    }
+
+    pub fn glyph(&self, c: char) -> u32 {
+        if let Some(glyph) = self.glyphs.get(&c) {
+            return *glyph;
+        }
+
+        if self.legacy && (c as u32) < 256 {
+            return c as u32;
+        }
+
+        self.fallback
+    }
+}
+
+impl Default for GlyphMap {
+    fn default() -> Self {
+        Self::cp437()
+    }
+}
+
+#[derive(Debug)]
+pub enum GlyphMapError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Key(String),
+}
+
+impl fmt::Display for GlyphMapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GlyphMapError::Io(error) => write!(f, "Failed to access glyph map: {}", error),
+            GlyphMapError::Json(error) => write!(f, "Failed to parse glyph map: {}", error),
+            GlyphMapError::Key(key) => {
+                write!(f, "Glyph map key '{}' is not a single character", key)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GlyphMapError {}
+
+impl From<std::io::Error> for GlyphMapError {
+    fn from(error: std::io::Error) -> Self {
+        GlyphMapError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for GlyphMapError {
+    fn from(error: serde_json::Error) -> Self {
+        GlyphMapError::Json(error)
+    }
+}
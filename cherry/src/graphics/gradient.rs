@@ -0,0 +1,76 @@
+use super::colour::Colour;
+
+#[derive(Debug, Clone, Default)]
+pub struct Gradient {
+    stops: Vec<(f32, Colour)>,
+    perceptual: bool,
+}
+
+impl Gradient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Spreads the colours evenly from 0 to 1.
+    pub fn even(colours: &[Colour]) -> Self {
+        let mut gradient = Self::new();
+        let last = colours.len().saturating_sub(1).max(1) as f32;
+        for (i, colour) in colours.iter().enumerate() {
+            gradient.add_stop(i as f32 / last, *colour);
+        }
+
+        gradient
+    }
+
+    pub fn add_stop(&mut self, position: f32, colour: Colour) {
+        // Keep the stops ordered by position. Stops at the same position
+        // keep the order in which they were added.
+        let index = self
+            .stops
+            .iter()
+            .position(|(p, _)| *p > position)
+            .unwrap_or(self.stops.len());
+
+        self.stops.insert(index, (position, colour));
+    }
+
+    pub fn stops(&self) -> &[(f32, Colour)] {
+        &self.stops
+    }
+
+    pub fn set_perceptual(&mut self, perceptual: bool) {
+        self.perceptual = perceptual;
+    }
+
+    pub fn sample(&self, t: f32) -> Colour {
+        let (first, last) = match (self.stops.first(), self.stops.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return Colour::TRANSPARENT,
+        };
+
+        if t <= first.0 {
+            return first.1;
+        }
+
+        if t >= last.0 {
+            return last.1;
+        }
+
+        for pair in self.stops.windows(2) {
+            let (p0, c0) = pair[0];
+            let (p1, c1) = pair[1];
+            if t > p1 {
+                continue;
+            }
+
+            let t = if p1 > p0 { (t - p0) / (p1 - p0) } else { 1.0 };
+            return if self.perceptual {
+                c0.lerp_perceptual(c1, t)
+            } else {
+                c0.lerp(c1, t)
+            };
+        }
+
+        last.1
+    }
+}
@@ -2,6 +2,7 @@
 pub struct Layout {
     pub elements: Vec<Element>,
     pub size: usize,
+    pub instanced: bool,
 }
 
 impl Layout {
@@ -9,6 +10,7 @@ impl Layout {
         Builder {
             elements: Vec::new(),
             size: 0,
+            instanced: false,
         }
     }
 }
@@ -20,11 +22,13 @@ pub struct Element {
     pub size: usize,
     pub offset: usize,
     pub data_type: u32,
+    pub integer: bool,
 }
 
 pub struct Builder {
     elements: Vec<Element>,
     size: usize,
+    instanced: bool,
 }
 
 impl Builder {
@@ -34,6 +38,7 @@ impl Builder {
         let element_size = kind.gl_size();
         let element_offset = self.size;
         let element_type = kind.gl_type();
+        let element_integer = kind.is_integer();
 
         let element = Element {
             index: element_index,
@@ -41,6 +46,7 @@ impl Builder {
             size: element_size,
             offset: element_offset,
             data_type: element_type,
+            integer: element_integer,
         };
 
         self.size += element_size;
@@ -49,10 +55,18 @@ impl Builder {
         self
     }
 
+    // Advance through the buffer once per instance, rather than once per
+    // vertex.
+    pub fn instanced(mut self) -> Self {
+        self.instanced = true;
+        self
+    }
+
     pub fn build(self) -> Layout {
         Layout {
             elements: self.elements,
             size: self.size,
+            instanced: self.instanced,
         }
     }
 }
@@ -60,6 +74,7 @@ impl Builder {
 #[derive(Debug)]
 pub enum ElementKind {
     Int,
+    UInt,
     Float,
     Float2,
     Float3,
@@ -70,6 +85,7 @@ impl ElementKind {
     fn gl_components(&self) -> u32 {
         match self {
             ElementKind::Int => 1,
+            ElementKind::UInt => 1,
             ElementKind::Float => 1,
             ElementKind::Float2 => 2,
             ElementKind::Float3 => 3,
@@ -80,6 +96,7 @@ impl ElementKind {
     fn gl_size(&self) -> usize {
         match self {
             ElementKind::Int => 4,
+            ElementKind::UInt => 4,
             ElementKind::Float => 4,
             ElementKind::Float2 => 8,
             ElementKind::Float3 => 12,
@@ -90,10 +107,15 @@ impl ElementKind {
     fn gl_type(&self) -> u32 {
         match self {
             ElementKind::Int => gl::INT,
+            ElementKind::UInt => gl::UNSIGNED_INT,
             ElementKind::Float => gl::FLOAT,
             ElementKind::Float2 => gl::FLOAT,
             ElementKind::Float3 => gl::FLOAT,
             ElementKind::Float4 => gl::FLOAT,
         }
     }
+
+    fn is_integer(&self) -> bool {
+        matches!(self, ElementKind::Int | ElementKind::UInt)
+    }
 }
@@ -1,10 +1,17 @@
-use blueberry::{
-    Vec2f,
-    Vec3f,
-};
+use std::collections::HashMap;
+
+use blueberry::Vec2f;
 
 use crate::{
-    graphics::font::Font,
+    graphics::{
+        colour::Colour,
+        font::{
+            Font,
+            GlyphMode,
+        },
+        quad::Quad,
+        sprite::Sprite,
+    },
     terminal::{
         buffer::Buffer,
         tile::Tile,
@@ -12,7 +19,6 @@ use crate::{
 };
 
 use super::{
-    index_buffer::IndexBuffer,
     layout::{
         ElementKind,
         Layout,
@@ -22,113 +28,220 @@ use super::{
         Shader,
         ShaderKind,
     },
+    texture::Texture,
     vertex_array::VertexArray,
     vertex_buffer::VertexBuffer,
 };
 
 pub struct Renderer {
     shader: Program,
-    vertex_buffer: VertexBuffer,
-    index_buffer: IndexBuffer,
-    vertex_array: VertexArray,
-    vertices: Vec<Vertex>,
+    passes: Vec<Pass>,
+    pass_count: usize,
+    last_pass_count: usize,
+    cells: Vec<Cell>,
+    textures: HashMap<u64, Texture>,
+    revisions: HashMap<u64, u32>,
+    view_size: Vec2f,
+    viewport: (i32, i32, u32, u32),
+    clear_colour: Colour,
+    dirty: bool,
+
+    // Quads
+    quad_shader: Program,
+    quad_buffer: VertexBuffer,
+    quad_array: VertexArray,
+    quad_capacity: usize,
+    vertices: Vec<QuadVertex>,
+    uploaded: Vec<QuadVertex>,
+    batches: Vec<Batch>,
+    drawn_batches: Vec<Batch>,
 }
 
+// One record per tile. The vertex shader builds the quad and the texture
+// coordinates of each cell from its index and glyph.
 #[repr(C)]
-#[derive(Copy, Clone)]
-struct Vertex {
-    position: Vec2f,
-    background: Vec3f,
-    foreground: Vec3f,
-    texture_coords: Vec2f,
+#[derive(Copy, Clone, PartialEq)]
+struct Cell {
+    glyph: u32,
+    foreground: u32,
+    background: u32,
 }
 
-impl Renderer {
-    pub fn new(columns: u32, rows: u32) -> Self {
-        // Initialise the layout for the vertex buffer.
-        let layout = Layout::builder()
-            .with(ElementKind::Float2) // Position
-            .with(ElementKind::Float3) // Foreground
-            .with(ElementKind::Float3) // Background
-            .with(ElementKind::Float2) // Texture Coords
-            .build();
+impl Cell {
+    fn new(font: &Font, tile: &Tile) -> Self {
+        Self {
+            glyph: font.glyph(tile.glyph),
+            foreground: pack(tile.fg, tile.transparent_glyph),
+            background: pack(tile.bg, tile.transparent_bg),
+        }
+    }
+}
 
-        // Calculate the maximum number of vertices and indices.
-        let cell_count = (columns * rows) as usize;
-        let vertice_count = cell_count * 4;
-        let indice_count = cell_count * 6;
+// Packs a colour for the shader to unpack with unpackUnorm4x8. A transparent
+// part of a tile has no alpha.
+fn pack(colour: Colour, transparent: bool) -> u32 {
+    let alpha = if transparent { 0 } else { colour.a as u32 };
+    colour.r as u32 | (colour.g as u32) << 8 | (colour.b as u32) << 16 | alpha << 24
+}
 
-        // Initialise vertice and indice data.
-        let vertices = Vec::with_capacity(vertice_count);
-        let mut indices = Vec::with_capacity(indice_count);
+// Quads are built on the CPU, as there are few of them compared to cells.
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq)]
+struct QuadVertex {
+    position: [f32; 2],
+    texture_coords: [f32; 2],
+    tint: u32,
+    mask: u32,
+}
 
-        // Generate indices. Since we are rendering quads, these are
-        // a one-time calculation.
-        let mut offset = 0;
-        for _ in 0..cell_count {
-            indices.push(offset + 0);
-            indices.push(offset + 1);
-            indices.push(offset + 2);
-            indices.push(offset + 2);
-            indices.push(offset + 3);
-            indices.push(offset + 0);
+// A run of consecutive quads that sample the same texture, drawn together.
+#[derive(Clone, PartialEq)]
+struct Batch {
+    texture: u64,
+    count: usize,
+}
 
-            offset += 4;
-        }
+// Each buffer drawn during a frame keeps its own instance buffer, along
+// with the cells last uploaded to it, so that unchanged cells are not
+// uploaded again on the next frame.
+struct Pass {
+    instance_buffer: VertexBuffer,
+    vertex_array: VertexArray,
+    capacity: usize,
+    texture: u64,
+    cell_size: Vec2f,
+    offset: Vec2f,
+    sheet: Sheet,
+    columns: u32,
+    cells: Vec<Cell>,
+}
 
-        let vertex_buffer_size = vertice_count * std::mem::size_of::<Vertex>();
-        let index_buffer_size = indice_count * std::mem::size_of::<u32>();
+// How the glyphs are laid out in a font's texture.
+#[derive(Copy, Clone, PartialEq)]
+struct Sheet {
+    columns: u32,
+    padding: u32,
+    spacing: u32,
+    mode: GlyphMode,
+}
 
-        let mut vertex_buffer = VertexBuffer::new(vertex_buffer_size);
-        vertex_buffer.set_layout(layout);
-        vertex_buffer.set_data(&vertices);
+impl Sheet {
+    fn new(font: &Font) -> Self {
+        Self {
+            columns: font.columns(),
+            padding: font.padding(),
+            spacing: font.spacing(),
+            mode: font.mode(),
+        }
+    }
+}
 
-        let mut index_buffer = IndexBuffer::new(index_buffer_size);
-        index_buffer.set_indices(&indices);
+impl Pass {
+    fn new(cell_count: usize) -> Self {
+        // Initialise the layout for the instance buffer.
+        let layout = Layout::builder()
+            .with(ElementKind::UInt) // Glyph
+            .with(ElementKind::UInt) // Foreground
+            .with(ElementKind::UInt) // Background
+            .instanced()
+            .build();
+
+        let mut instance_buffer = VertexBuffer::new(cell_count * std::mem::size_of::<Cell>());
+        instance_buffer.set_layout(layout);
 
         let mut vertex_array = VertexArray::new();
-        vertex_array.set_vertex_buffer(&vertex_buffer);
-        vertex_array.set_index_buffer(&index_buffer);
+        vertex_array.set_vertex_buffer(&instance_buffer);
+
+        Self {
+            instance_buffer,
+            vertex_array,
+            capacity: cell_count,
+            texture: u64::MAX,
+            cell_size: Vec2f::zero(),
+            offset: Vec2f::zero(),
+            sheet: Sheet {
+                columns: 16,
+                padding: 0,
+                spacing: 0,
+                mode: GlyphMode::Mask,
+            },
+            columns: 0,
+            cells: Vec::new(),
+        }
+    }
+}
 
+impl Renderer {
+    pub fn new(columns: u32, rows: u32, font: &Font) -> Self {
         let vertex_shader_source = r#"#version 450 core
-        layout (location = 0) in vec2 a_vertex_position;
-        layout (location = 1) in vec3 a_foreground;
-        layout (location = 2) in vec3 a_background;
-        layout (location = 3) in vec2 a_texture_coords;
+        layout (location = 0) in uint a_glyph;
+        layout (location = 1) in uint a_foreground;
+        layout (location = 2) in uint a_background;
         
-        out vec3 foreground;
-        out vec3 background;
+        out vec4 foreground;
+        out vec4 background;
         out vec2 texture_coords;
         
-        uniform vec2 font_size;
         uniform vec2 view_size;
+        uniform vec2 offset;
+        uniform vec2 cell_size;
+        uniform vec2 texture_size;
+        uniform uint columns;
+        uniform uint sheet_columns;
+        uniform float padding;
+        uniform float spacing;
         
         void main() {
-            texture_coords = a_texture_coords;
-            foreground = a_foreground;
-            background = a_background;
+            // The quad is drawn as a strip of four vertices.
+            vec2 corner = vec2(gl_VertexID & 1, gl_VertexID >> 1);
+        
+            uint index = uint(gl_InstanceID);
+            vec2 cell = vec2(index % columns, index / columns);
+            vec2 glyph = vec2(a_glyph % sheet_columns, a_glyph / sheet_columns);
+            vec2 glyph_position = vec2(padding) + glyph * (cell_size + vec2(spacing));
         
-            vec2 vertex_position = ((a_vertex_position * font_size) / (view_size / 2.0)) - vec2(1.0, 1.0);
+            texture_coords = (glyph_position + corner * cell_size) / texture_size;
+            foreground = unpackUnorm4x8(a_foreground);
+            background = unpackUnorm4x8(a_background);
+        
+            // Positions are in pixels from the top-left of the view.
+            vec2 vertex_position = offset + (cell + corner) * cell_size;
+            vertex_position = vertex_position / (view_size / 2.0);
+            vertex_position = vec2(vertex_position.x - 1.0, 1.0 - vertex_position.y);
         
             gl_Position = vec4(vertex_position, 0.0, 1.0);
         }"#;
 
         let fragment_shader_source = r#"#version 450 core
         uniform sampler2D u_texture;
+        uniform int colour_glyphs;
         
-        in vec3 foreground;
-        in vec3 background;
+        in vec4 foreground;
+        in vec4 background;
         in vec2 texture_coords;
         
         out vec4 fragment_colour;
         
         void main() {
-            vec3 sample_colour = texture(u_texture, texture_coords).xyz;
-            vec3 foreground_mask = (vec3(1.0) - sample_colour) * foreground;
-            vec3 background_mask = sample_colour * background;
-            vec3 colour = foreground_mask + background_mask;
+            vec4 texel = texture(u_texture, texture_coords);
         
-            fragment_colour = vec4(colour, 1.0);
+            // Mask glyphs choose between the foreground and background by
+            // brightness. Colour glyphs cover the background by their alpha.
+            vec3 sample_colour = texel.rgb;
+            vec3 glyph_colour = foreground.rgb;
+            if (colour_glyphs != 0) {
+                sample_colour = vec3(texel.a);
+                glyph_colour = texel.rgb;
+            }
+        
+            vec3 foreground_mask = sample_colour * foreground.a;
+            vec3 background_mask = (vec3(1.0) - sample_colour) * background.a;
+            vec3 colour = foreground_mask * glyph_colour + background_mask * background.rgb;
+        
+            // The colour is premultiplied, so transparent parts of the tile
+            // leave whatever was drawn underneath.
+            float alpha = dot(foreground_mask + background_mask, vec3(1.0 / 3.0));
+            fragment_colour = vec4(colour, alpha);
         }"#;
 
         let shader = Program::new(&[
@@ -136,108 +249,382 @@ impl Renderer {
             Shader::new(ShaderKind::Fragment, fragment_shader_source),
         ]);
 
-        Self {
+        let quad_vertex_shader_source = r#"#version 450 core
+        layout (location = 0) in vec2 a_position;
+        layout (location = 1) in vec2 a_texture_coords;
+        layout (location = 2) in uint a_tint;
+        layout (location = 3) in uint a_mask;
+        
+        out vec2 texture_coords;
+        out vec4 tint;
+        flat out uint mask;
+        
+        uniform vec2 view_size;
+        
+        void main() {
+            texture_coords = a_texture_coords;
+            tint = unpackUnorm4x8(a_tint);
+            mask = a_mask;
+        
+            // Positions are in pixels from the top-left of the view.
+            vec2 vertex_position = a_position / (view_size / 2.0);
+            vertex_position = vec2(vertex_position.x - 1.0, 1.0 - vertex_position.y);
+        
+            gl_Position = vec4(vertex_position, 0.0, 1.0);
+        }"#;
+
+        let quad_fragment_shader_source = r#"#version 450 core
+        uniform sampler2D u_texture;
+        
+        in vec2 texture_coords;
+        in vec4 tint;
+        flat in uint mask;
+        
+        out vec4 fragment_colour;
+        
+        void main() {
+            vec4 texel = texture(u_texture, texture_coords);
+        
+            // Mask glyphs are drawn in the tint where they are light.
+            // Anything else is multiplied by the tint.
+            vec4 colour = texel * tint;
+            if (mask != 0u) {
+                colour = vec4(tint.rgb, dot(texel.rgb, vec3(1.0 / 3.0)) * tint.a);
+            }
+        
+            fragment_colour = vec4(colour.rgb * colour.a, colour.a);
+        }"#;
+
+        let quad_shader = Program::new(&[
+            Shader::new(ShaderKind::Vertex, quad_vertex_shader_source),
+            Shader::new(ShaderKind::Fragment, quad_fragment_shader_source),
+        ]);
+
+        let (quad_buffer, quad_array) = create_quad_buffer(QUAD_CAPACITY);
+
+        let mut renderer = Self {
             shader,
-            vertex_buffer,
-            index_buffer,
-            vertex_array,
-            vertices,
+            passes: Vec::new(),
+            pass_count: 0,
+            last_pass_count: 0,
+            cells: Vec::with_capacity((columns * rows) as usize),
+            textures: HashMap::new(),
+            revisions: HashMap::new(),
+            view_size: Vec2f::new(
+                (columns * font.width()) as f32,
+                (rows * font.height()) as f32,
+            ),
+            viewport: (0, 0, columns * font.width(), rows * font.height()),
+            clear_colour: Colour::BLACK,
+            dirty: true,
+            quad_shader,
+            quad_buffer,
+            quad_array,
+            quad_capacity: QUAD_CAPACITY,
+            vertices: Vec::new(),
+            uploaded: Vec::new(),
+            batches: Vec::new(),
+            drawn_batches: Vec::new(),
+        };
+
+        renderer.set_font(font);
+        renderer
+    }
+
+    pub fn set_font(&mut self, font: &Font) {
+        self.set_texture(font.sprite(), font.revision());
+    }
+
+    fn set_texture(&mut self, sprite: &Sprite, revision: u32) {
+        // Replace any texture from an earlier revision of the same sprite.
+        self.textures.insert(sprite.id(), create_texture(sprite));
+        self.revisions.insert(sprite.id(), revision);
+
+        self.dirty = true;
+    }
+
+    pub fn set_size(&mut self, width: u32, height: u32) {
+        self.view_size = Vec2f::new(width as f32, height as f32);
+        self.dirty = true;
+    }
+
+    // The part of the window the view is scaled to, from the bottom-left.
+    pub fn set_viewport(&mut self, x: i32, y: i32, width: u32, height: u32) {
+        self.viewport = (x, y, width, height);
+        self.dirty = true;
+    }
+
+    // Draws the next frame even if nothing changed.
+    pub fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
+    pub fn begin(&mut self, clear_colour: Colour) {
+        if clear_colour != self.clear_colour {
+            self.clear_colour = clear_colour;
+            self.dirty = true;
         }
+
+        self.pass_count = 0;
+        self.vertices.clear();
+        self.batches.clear();
     }
 
-    fn draw_tile(&mut self, font: &Font, position: Vec2f, tile: &Tile) {
-        // Calculate vertex positions.
-        let positions = [
-            position + Vec2f::new(0.0, 0.0),
-            position + Vec2f::new(1.0, 0.0),
-            position + Vec2f::new(1.0, 1.0),
-            position + Vec2f::new(0.0, 1.0),
-        ];
-
-        // Normalise colours.
-        let foreground = Vec3f::new(
-            tile.fg.r as f32 / 255.0,
-            tile.fg.g as f32 / 255.0,
-            tile.fg.b as f32 / 255.0,
-        );
-
-        let background = Vec3f::new(
-            tile.bg.r as f32 / 255.0,
-            tile.bg.g as f32 / 255.0,
-            tile.bg.b as f32 / 255.0,
-        );
-
-        // Column and row of glyph into font sheet.
-        let column = tile.glyph as u32 % 16;
-        let row = tile.glyph as u32 / 16;
-
-        // The bottom-left coordinates of the glyph.
-        let x = column * font.width();
-        let y = row * font.height();
-
-        // Calculate sub-texture coordinates of the glyph.
-        let u0 = x as f32 / font.texture().width() as f32;
-        let v0 = y as f32 / font.texture().height() as f32;
-        let u1 = (x + font.width()) as f32 / font.texture().width() as f32;
-        let v1 = (y + font.height()) as f32 / font.texture().height() as f32;
-
-        let texture_coords = [
-            Vec2f::new(u0, v1),
-            Vec2f::new(u1, v1),
-            Vec2f::new(u1, v0),
-            Vec2f::new(u0, v0),
-        ];
-
-        for i in 0..4 {
-            let vertex = Vertex {
-                position: positions[i],
-                foreground,
-                background,
-                texture_coords: texture_coords[i],
-            };
-
-            self.vertices.push(vertex);
+    pub fn draw_buffer(&mut self, font: &Font, offset: Vec2f, buffer: &Buffer) {
+        // Upload the font's sheet the first time it is used, and again
+        // whenever glyphs are added to it.
+        let texture = font.sprite().id();
+        if self.revisions.get(&texture) != Some(&font.revision()) {
+            self.set_font(font);
+        }
+
+        let cell_count = buffer.size() as usize;
+        if self.pass_count == self.passes.len() {
+            self.passes.push(Pass::new(cell_count));
+        }
+
+        let pass = &mut self.passes[self.pass_count];
+        self.pass_count += 1;
+
+        if cell_count > pass.capacity {
+            *pass = Pass::new(cell_count);
+        }
+
+        // Where the cells are drawn only affects the uniforms.
+        let cell_size = Vec2f::new(font.width() as f32, font.height() as f32);
+        let sheet = Sheet::new(font);
+        if pass.texture != texture
+            || pass.cell_size != cell_size
+            || pass.offset != offset
+            || pass.sheet != sheet
+        {
+            pass.texture = texture;
+            pass.cell_size = cell_size;
+            pass.offset = offset;
+            pass.sheet = sheet;
+            self.dirty = true;
+        }
+
+        if pass.columns != buffer.columns() || pass.cells.len() != cell_count {
+            self.cells
+                .extend(buffer.data().iter().map(|tile| Cell::new(font, tile)));
+            pass.instance_buffer.set_data(&self.cells);
+
+            pass.columns = buffer.columns();
+            pass.cells = self.cells.split_off(0);
+            self.dirty = true;
+            return;
+        }
+
+        // Upload each run of changed cells with a single update.
+        let tiles = buffer.data();
+        let mut index = 0;
+        while index < cell_count {
+            let mut cell = Cell::new(font, &tiles[index]);
+            if cell == pass.cells[index] {
+                index += 1;
+                continue;
+            }
+
+            let start = index;
+            while cell != pass.cells[index] {
+                pass.cells[index] = cell;
+                self.cells.push(cell);
+
+                index += 1;
+                if index == cell_count {
+                    break;
+                }
+
+                cell = Cell::new(font, &tiles[index]);
+            }
+
+            let byte_offset = start * std::mem::size_of::<Cell>();
+            pass.instance_buffer.set_sub_data(byte_offset, &self.cells);
+            self.cells.clear();
+            self.dirty = true;
         }
     }
 
-    pub fn draw_buffer(&mut self, font: &Font, offset: Vec2f, buffer: &mut Buffer) {
-        for r in 0..buffer.rows() {
-            for c in 0..buffer.columns() {
-                let position = offset + Vec2f::new(c as f32, (buffer.rows() - r - 1) as f32);
-                let tile = &mut buffer.get_at(c as i32, r as i32).unwrap();
+    pub fn draw_quads(&mut self, quads: &[Quad]) {
+        for quad in quads {
+            let texture = quad.texture.id();
+            if self.revisions.get(&texture) != Some(&quad.revision) {
+                self.set_texture(quad.texture, quad.revision);
+            }
 
-                self.draw_tile(font, position, tile);
+            match self.batches.last_mut() {
+                Some(batch) if batch.texture == texture => batch.count += 1,
+                _ => self.batches.push(Batch { texture, count: 1 }),
             }
+
+            // Two triangles, sharing the diagonal from top-right to
+            // bottom-left.
+            let corners = quad.corners();
+            let (x, y, w, h) = quad.source;
+            let size = Vec2f::new(quad.texture.width() as f32, quad.texture.height() as f32);
+            let uv = |u: u32, v: u32| [u as f32 / size.x, v as f32 / size.y];
+            let texture_coords = [uv(x, y), uv(x + w, y), uv(x, y + h), uv(x + w, y + h)];
+
+            let tint = pack(quad.tint, false);
+            for i in [0, 1, 2, 1, 3, 2] {
+                self.vertices.push(QuadVertex {
+                    position: [corners[i].x, corners[i].y],
+                    texture_coords: texture_coords[i],
+                    tint,
+                    mask: quad.mask as u32,
+                });
+            }
+        }
+    }
+
+    // Draws the frame, unless it would look the same as the last one.
+    // Returns whether anything was drawn.
+    pub fn end(&mut self) -> bool {
+        if self.pass_count != self.last_pass_count {
+            self.last_pass_count = self.pass_count;
+            self.dirty = true;
+        }
+
+        if self.vertices != self.uploaded || self.batches != self.drawn_batches {
+            self.upload_quads();
+            self.dirty = true;
         }
 
-        self.vertex_buffer.set_data(&self.vertices);
+        if !self.dirty {
+            return false;
+        }
 
         unsafe {
+            gl::ClearColor(
+                self.clear_colour.r as f32 / 255.0,
+                self.clear_colour.g as f32 / 255.0,
+                self.clear_colour.b as f32 / 255.0,
+                1.0,
+            );
+            // The whole window is cleared, so that any border around the
+            // viewport is the clear colour too.
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+
+            let (x, y, width, height) = self.viewport;
+            gl::Viewport(x, y, width as i32, height as i32);
+
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::ONE, gl::ONE_MINUS_SRC_ALPHA);
             gl::UseProgram(self.shader.id());
-            gl::BindTextureUnit(0, font.texture().id());
-            gl::BindVertexArray(self.vertex_array.id());
 
             self.shader
-                .uniform_2f("font_size", font.width() as f32, font.height() as f32);
-            self.shader.uniform_2f(
-                "view_size",
-                (font.width() * buffer.columns()) as f32,
-                (font.height() * buffer.rows()) as f32,
-            );
-            self.shader
-                .uniform_1i("texture", font.texture().id() as i32);
+                .uniform_2f("view_size", self.view_size.x, self.view_size.y);
+        }
 
-            gl::DrawElements(
-                gl::TRIANGLES,
-                self.index_buffer.len() as i32,
-                gl::UNSIGNED_INT,
-                std::ptr::null(),
-            );
+        // Passes are drawn in order, so that layers overlap correctly, but
+        // a texture is only bound again when the font changes between them.
+        let mut bound: Option<(u64, Sheet)> = None;
+        for pass in &self.passes[..self.pass_count] {
+            unsafe {
+                if bound != Some((pass.texture, pass.sheet)) {
+                    let texture = &self.textures[&pass.texture];
+                    gl::BindTextureUnit(0, texture.id());
+
+                    self.shader.uniform_1i("texture", texture.id() as i32);
+                    self.shader.uniform_2f(
+                        "texture_size",
+                        texture.width() as f32,
+                        texture.height() as f32,
+                    );
+                    self.shader.uniform_1u("sheet_columns", pass.sheet.columns);
+                    self.shader.uniform_1f("padding", pass.sheet.padding as f32);
+                    self.shader.uniform_1f("spacing", pass.sheet.spacing as f32);
+                    self.shader.uniform_1i(
+                        "colour_glyphs",
+                        (pass.sheet.mode == GlyphMode::Colour) as i32,
+                    );
+
+                    bound = Some((pass.texture, pass.sheet));
+                }
+
+                gl::BindVertexArray(pass.vertex_array.id());
+
+                self.shader
+                    .uniform_2f("offset", pass.offset.x, pass.offset.y);
+                self.shader
+                    .uniform_2f("cell_size", pass.cell_size.x, pass.cell_size.y);
+                self.shader.uniform_1u("columns", pass.columns);
+
+                gl::DrawArraysInstanced(gl::TRIANGLE_STRIP, 0, 4, pass.cells.len() as i32);
+            }
+        }
+
+        // Quads are drawn over every buffer.
+        unsafe {
+            gl::UseProgram(self.quad_shader.id());
+            gl::BindVertexArray(self.quad_array.id());
+
+            self.quad_shader
+                .uniform_2f("view_size", self.view_size.x, self.view_size.y);
+        }
+
+        let mut first = 0;
+        for batch in &self.drawn_batches {
+            let count = batch.count * 6;
+
+            unsafe {
+                gl::BindTextureUnit(0, self.textures[&batch.texture].id());
+                gl::DrawArrays(gl::TRIANGLES, first as i32, count as i32);
+            }
+
+            first += count;
+        }
+
+        unsafe {
             gl::BindVertexArray(0);
             gl::BindTextureUnit(0, 0);
             gl::UseProgram(0);
+            gl::Disable(gl::BLEND);
         }
 
-        self.vertices.clear();
+        self.dirty = false;
+        true
+    }
+
+    fn upload_quads(&mut self) {
+        // Grow the buffer when there are more quads than fit in it.
+        if self.vertices.len() > self.quad_capacity * 6 {
+            while self.vertices.len() > self.quad_capacity * 6 {
+                self.quad_capacity *= 2;
+            }
+
+            let (quad_buffer, quad_array) = create_quad_buffer(self.quad_capacity);
+            self.quad_buffer = quad_buffer;
+            self.quad_array = quad_array;
+        }
+
+        self.quad_buffer.set_data(&self.vertices);
+        self.uploaded.clone_from(&self.vertices);
+        self.drawn_batches.clone_from(&self.batches);
     }
 }
+
+// The number of quads the buffer has room for at first.
+const QUAD_CAPACITY: usize = 256;
+
+fn create_quad_buffer(capacity: usize) -> (VertexBuffer, VertexArray) {
+    let layout = Layout::builder()
+        .with(ElementKind::Float2) // Position
+        .with(ElementKind::Float2) // Texture coordinates
+        .with(ElementKind::UInt) // Tint
+        .with(ElementKind::UInt) // Mask
+        .build();
+
+    let mut quad_buffer = VertexBuffer::new(capacity * 6 * std::mem::size_of::<QuadVertex>());
+    quad_buffer.set_layout(layout);
+
+    let mut quad_array = VertexArray::new();
+    quad_array.set_vertex_buffer(&quad_buffer);
+
+    (quad_buffer, quad_array)
+}
+
+fn create_texture(sprite: &Sprite) -> Texture {
+    Texture::new(sprite.width(), sprite.height(), sprite.data())
+}
@@ -19,11 +19,11 @@ impl Texture {
             gl::TexImage2D(
                 gl::TEXTURE_2D,
                 0,
-                gl::RGB as _,
+                gl::RGBA as _,
                 width as _,
                 height as _,
                 0,
-                gl::RGB,
+                gl::RGBA,
                 gl::UNSIGNED_BYTE,
                 data.as_ptr() as _,
             );
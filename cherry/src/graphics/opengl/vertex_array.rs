@@ -27,14 +27,30 @@ impl VertexArray {
         for element in &layout.elements {
             unsafe {
                 gl::EnableVertexAttribArray(element.index as u32);
-                gl::VertexAttribPointer(
-                    element.index as _,
-                    element.components as _,
-                    element.data_type,
-                    gl::FALSE,
-                    layout.size as _,
-                    element.offset as _,
-                );
+
+                // Integer attributes are passed to the shader unconverted.
+                if element.integer {
+                    gl::VertexAttribIPointer(
+                        element.index as _,
+                        element.components as _,
+                        element.data_type,
+                        layout.size as _,
+                        element.offset as _,
+                    );
+                } else {
+                    gl::VertexAttribPointer(
+                        element.index as _,
+                        element.components as _,
+                        element.data_type,
+                        gl::FALSE,
+                        layout.size as _,
+                        element.offset as _,
+                    );
+                }
+
+                if layout.instanced {
+                    gl::VertexAttribDivisor(element.index as _, 1);
+                }
             }
         }
 
@@ -51,6 +51,25 @@ impl VertexBuffer {
         }
     }
 
+    pub fn set_sub_data<T: Sized>(&mut self, offset: usize, data: &[T]) {
+        unsafe {
+            let data = as_u8_slice(data);
+            let size = data.len();
+
+            assert!(
+                offset + size <= self.capacity,
+                "Attempt to update vertex buffer at offset {} with data size {} when its capacity is {}.",
+                offset,
+                size,
+                self.capacity
+            );
+
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.id);
+            gl::BufferSubData(gl::ARRAY_BUFFER, offset as _, size as _, data.as_ptr() as _);
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+        }
+    }
+
     pub fn id(&self) -> u32 {
         self.id
     }
@@ -0,0 +1,168 @@
+use std::{
+    fmt,
+    fs,
+    path::Path,
+};
+
+use super::colour::Colour;
+
+#[derive(Debug, Clone, Default)]
+pub struct Palette {
+    colours: Vec<Colour>,
+}
+
+impl Palette {
+    pub fn new(colours: Vec<Colour>) -> Self {
+        Self { colours }
+    }
+
+    // The format is chosen by the extension: `.hex` holds one hex colour
+    // per line, `.gpl` is a GIMP palette, and `.json` is an array of hex
+    // colours.
+    pub fn load(path: &str) -> Result<Self, PaletteError> {
+        let source = fs::read_to_string(path)?;
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase());
+
+        match extension.as_deref() {
+            Some("hex") => Self::parse_hex(&source),
+            Some("gpl") => Self::parse_gpl(&source),
+            Some("json") => Self::parse_json(&source),
+            _ => Err(PaletteError::Format(format!(
+                "Unknown palette format '{}'",
+                path
+            ))),
+        }
+    }
+
+    pub fn parse_hex(source: &str) -> Result<Self, PaletteError> {
+        let mut colours = Vec::new();
+        for line in source.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            colours.push(parse_colour(line)?);
+        }
+
+        Ok(Self::new(colours))
+    }
+
+    pub fn parse_gpl(source: &str) -> Result<Self, PaletteError> {
+        let mut lines = source.lines();
+        if lines.next().map(str::trim) != Some("GIMP Palette") {
+            return Err(PaletteError::Format(String::from(
+                "Missing the 'GIMP Palette' header",
+            )));
+        }
+
+        let mut colours = Vec::new();
+        for line in lines {
+            let line = line.trim();
+            if line.is_empty()
+                || line.starts_with('#')
+                || line.starts_with("Name:")
+                || line.starts_with("Columns:")
+            {
+                continue;
+            }
+
+            // Each entry is a red, green and blue value, then an optional
+            // name.
+            let mut values = line.split_whitespace().map(|value| value.parse::<u8>());
+            let mut channel = || match values.next() {
+                Some(Ok(value)) => Ok(value),
+                _ => Err(PaletteError::Format(format!(
+                    "Invalid palette entry '{}'",
+                    line
+                ))),
+            };
+
+            colours.push(Colour::new(channel()?, channel()?, channel()?));
+        }
+
+        Ok(Self::new(colours))
+    }
+
+    pub fn parse_json(source: &str) -> Result<Self, PaletteError> {
+        let entries: Vec<String> = serde_json::from_str(source)?;
+        let colours = entries
+            .iter()
+            .map(|entry| parse_colour(entry))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self::new(colours))
+    }
+
+    pub fn colours(&self) -> &[Colour] {
+        &self.colours
+    }
+
+    pub fn len(&self) -> usize {
+        self.colours.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.colours.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<Colour> {
+        self.colours.get(index).copied()
+    }
+
+    pub fn nearest(&self, colour: Colour) -> Option<usize> {
+        self.colours
+            .iter()
+            .map(|entry| entry.distance(colour))
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(index, _)| index)
+    }
+
+    // The palette entry closest to the colour, keeping the colour's alpha.
+    pub fn quantise(&self, colour: Colour) -> Colour {
+        match self.nearest(colour) {
+            Some(index) => self.colours[index].with_alpha(colour.a),
+            None => colour,
+        }
+    }
+}
+
+fn parse_colour(hex: &str) -> Result<Colour, PaletteError> {
+    Colour::from_hex(hex)
+        .ok_or_else(|| PaletteError::Format(format!("Invalid hex colour '{}'", hex)))
+}
+
+#[derive(Debug)]
+pub enum PaletteError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Format(String),
+}
+
+impl fmt::Display for PaletteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaletteError::Io(error) => write!(f, "Failed to access palette: {}", error),
+            PaletteError::Json(error) => write!(f, "Failed to parse palette: {}", error),
+            PaletteError::Format(error) => write!(f, "Failed to parse palette: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for PaletteError {}
+
+impl From<std::io::Error> for PaletteError {
+    fn from(error: std::io::Error) -> Self {
+        PaletteError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for PaletteError {
+    fn from(error: serde_json::Error) -> Self {
+        PaletteError::Json(error)
+    }
+}
@@ -0,0 +1,51 @@
+use blueberry::Vec2f;
+
+use super::{
+    colour::Colour,
+    font::Font,
+    sprite::Sprite,
+};
+
+// A region of a texture drawn anywhere on screen, rather than in a cell.
+pub struct Quad<'a> {
+    pub texture: &'a Sprite,
+    pub revision: u32,
+    pub source: (u32, u32, u32, u32),
+    pub position: Vec2f,
+    pub scale: Vec2f,
+    pub rotation: f32,
+    pub tint: Colour,
+    pub mask: bool,
+    // The character shown by a glyph and its font, for backends that draw
+    // text.
+    pub glyph: Option<(&'a Font, char)>,
+}
+
+impl Quad<'_> {
+    // The corners of the quad in pixels, in the order top-left, top-right,
+    // bottom-left, bottom-right. The position is the top-left of the
+    // unscaled region, and the quad is scaled and rotated about its centre.
+    pub fn corners(&self) -> [Vec2f; 4] {
+        let (_, _, w, h) = self.source;
+        let half = Vec2f::new(w as f32, h as f32) / 2.0;
+        let centre = self.position + half;
+
+        let (sin, cos) = self.rotation.sin_cos();
+        let corner = |x: f32, y: f32| {
+            let offset = Vec2f::new(x, y) * half * self.scale;
+            let rotated = Vec2f::new(
+                offset.x * cos - offset.y * sin,
+                offset.x * sin + offset.y * cos,
+            );
+
+            centre + rotated
+        };
+
+        [
+            corner(-1.0, -1.0),
+            corner(1.0, -1.0),
+            corner(-1.0, 1.0),
+            corner(1.0, 1.0),
+        ]
+    }
+}
@@ -0,0 +1,211 @@
+use blueberry::Vec2f;
+use image::{
+    ImageResult,
+    Rgba,
+    RgbaImage,
+};
+
+use crate::{
+    graphics::{
+        colour::Colour,
+        font::{
+            Font,
+            GlyphMode,
+        },
+        quad::Quad,
+    },
+    terminal::{
+        buffer::Buffer,
+        tile::Tile,
+    },
+};
+
+pub struct Rasterizer {
+    image: RgbaImage,
+}
+
+impl Rasterizer {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            image: RgbaImage::new(width, height),
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.image.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.image.height()
+    }
+
+    pub fn image(&self) -> &RgbaImage {
+        &self.image
+    }
+
+    pub fn save(&self, path: &str) -> ImageResult<()> {
+        self.image.save(path)
+    }
+
+    pub fn clear(&mut self, colour: Colour) {
+        let pixel = Rgba([colour.r, colour.g, colour.b, colour.a]);
+        for p in self.image.pixels_mut() {
+            *p = pixel;
+        }
+    }
+
+    pub fn draw_buffer(&mut self, font: &Font, offset: Vec2f, buffer: &Buffer) {
+        for r in 0..buffer.rows() {
+            for c in 0..buffer.columns() {
+                let position =
+                    offset + Vec2f::new((c * font.width()) as f32, (r * font.height()) as f32);
+                let tile = buffer.get_at(c as i32, r as i32).unwrap();
+
+                self.draw_tile(font, position, tile);
+            }
+        }
+    }
+
+    pub fn draw_quads(&mut self, quads: &[Quad]) {
+        for quad in quads {
+            self.draw_quad(quad);
+        }
+    }
+
+    fn draw_quad(&mut self, quad: &Quad) {
+        let (sx, sy, w, h) = quad.source;
+        let half = Vec2f::new(w as f32, h as f32) * quad.scale / 2.0;
+        if half.x == 0.0 || half.y == 0.0 {
+            return;
+        }
+
+        // The pixels the quad could cover.
+        let corners = quad.corners();
+        let min_x = corners.iter().map(|c| c.x).fold(f32::MAX, f32::min).floor() as i32;
+        let max_x = corners.iter().map(|c| c.x).fold(f32::MIN, f32::max).ceil() as i32;
+        let min_y = corners.iter().map(|c| c.y).fold(f32::MAX, f32::min).floor() as i32;
+        let max_y = corners.iter().map(|c| c.y).fold(f32::MIN, f32::max).ceil() as i32;
+
+        let centre = (corners[0] + corners[3]) / 2.0;
+        let (sin, cos) = quad.rotation.sin_cos();
+
+        for py in min_y.max(0)..max_y.min(self.image.height() as i32) {
+            for px in min_x.max(0)..max_x.min(self.image.width() as i32) {
+                // Undo the rotation and scale to find the texel under the
+                // centre of the pixel, from -1 to 1 across the quad.
+                let offset = Vec2f::new(px as f32 + 0.5, py as f32 + 0.5) - centre;
+                let local = Vec2f::new(
+                    offset.x * cos + offset.y * sin,
+                    offset.y * cos - offset.x * sin,
+                ) / half;
+                if local.x.abs() > 1.0 || local.y.abs() > 1.0 {
+                    continue;
+                }
+
+                let u = ((local.x + 1.0) / 2.0 * w as f32).min(w as f32 - 1.0) as i32;
+                let v = ((local.y + 1.0) / 2.0 * h as f32).min(h as f32 - 1.0) as i32;
+                let texel = quad
+                    .texture
+                    .get_at(sx as i32 + u, sy as i32 + v)
+                    .copied()
+                    .unwrap_or(Colour::TRANSPARENT);
+
+                // The same rule as the quad fragment shader: mask glyphs are
+                // drawn in the tint where they are light, and everything else
+                // is multiplied by the tint.
+                let tint = [quad.tint.r, quad.tint.g, quad.tint.b];
+                let (colour, alpha) = if quad.mask {
+                    let brightness = (texel.r as f32 + texel.g as f32 + texel.b as f32) / 765.0;
+                    let alpha = brightness * quad.tint.a as f32 / 255.0;
+                    (tint.map(|c| c as f32 * alpha), alpha)
+                } else {
+                    let alpha = texel.a as f32 * quad.tint.a as f32 / 65025.0;
+                    let texel = [texel.r, texel.g, texel.b];
+                    let colour =
+                        [0, 1, 2].map(|i| texel[i] as f32 * tint[i] as f32 / 255.0 * alpha);
+
+                    (colour, alpha)
+                };
+
+                let under = self.image.get_pixel(px as u32, py as u32);
+                let mut pixel = [0, 0, 0, 255];
+                for (i, value) in colour.iter().enumerate() {
+                    pixel[i] = (value + under[i] as f32 * (1.0 - alpha)).round() as u8;
+                }
+
+                self.image.put_pixel(px as u32, py as u32, Rgba(pixel));
+            }
+        }
+    }
+
+    fn draw_tile(&mut self, font: &Font, position: Vec2f, tile: &Tile) {
+        if tile.transparent_glyph && tile.transparent_bg {
+            return;
+        }
+
+        // The top-left coordinates of the glyph.
+        let (gx, gy) = font.glyph_position(font.glyph(tile.glyph));
+        let (gx, gy) = (gx as i32, gy as i32);
+
+        // Transparent parts of the tile show the pixel underneath.
+        let fg_alpha = if tile.transparent_glyph { 0 } else { tile.fg.a };
+        let bg_alpha = if tile.transparent_bg { 0 } else { tile.bg.a };
+
+        // The top-left coordinates of the cell in the image.
+        let x0 = position.x as i32;
+        let y0 = position.y as i32;
+
+        for y in 0..font.height() as i32 {
+            for x in 0..font.width() as i32 {
+                let px = x0 + x;
+                let py = y0 + y;
+                if px < 0
+                    || py < 0
+                    || px >= self.image.width() as i32
+                    || py >= self.image.height() as i32
+                {
+                    continue;
+                }
+
+                let sample = font
+                    .sprite()
+                    .get_at(gx + x, gy + y)
+                    .copied()
+                    .unwrap_or(Colour::TRANSPARENT);
+
+                // Colour glyphs cover the background by their alpha, in
+                // their own colours.
+                let (weights, fg) = match font.mode() {
+                    GlyphMode::Mask => ([sample.r, sample.g, sample.b], tile.fg),
+                    GlyphMode::Colour => ([sample.a; 3], sample),
+                };
+
+                let under = self.image.get_pixel(px as u32, py as u32);
+                let masks = [
+                    mask(weights[0], fg.r, fg_alpha, tile.bg.r, bg_alpha),
+                    mask(weights[1], fg.g, fg_alpha, tile.bg.g, bg_alpha),
+                    mask(weights[2], fg.b, fg_alpha, tile.bg.b, bg_alpha),
+                ];
+                let coverage = masks.iter().map(|(_, alpha)| alpha).sum::<f32>() / 3.0;
+
+                let mut pixel = [0, 0, 0, 255];
+                for (i, (colour, _)) in masks.iter().enumerate() {
+                    pixel[i] = (colour + under[i] as f32 * (1.0 - coverage)).round() as u8;
+                }
+
+                self.image.put_pixel(px as u32, py as u32, Rgba(pixel));
+            }
+        }
+    }
+}
+
+// The same rule as the fragment shader: light texels take the foreground
+// colour and dark texels take the background colour, each weighted by its
+// alpha. Returns the premultiplied colour and its coverage.
+fn mask(sample: u8, fg: u8, fg_alpha: u8, bg: u8, bg_alpha: u8) -> (f32, f32) {
+    let sample = sample as f32 / 255.0;
+    let fg_mask = sample * fg_alpha as f32 / 255.0;
+    let bg_mask = (1.0 - sample) * bg_alpha as f32 / 255.0;
+
+    (fg as f32 * fg_mask + bg as f32 * bg_mask, fg_mask + bg_mask)
+}
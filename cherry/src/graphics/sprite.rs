@@ -1,12 +1,31 @@
+use std::sync::atomic::{
+    AtomicU64,
+    Ordering,
+};
+
 use super::colour::Colour;
 use blueberry::Grid;
 
+// Every sprite has its own id, so that sprites with the same path can still
+// be told apart.
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
 pub struct Sprite {
+    id: u64,
     path: String,
     grid: Grid<Colour>,
 }
 
 impl Sprite {
+    // A blank sprite to draw into. The path only names it.
+    pub fn new(path: &str, width: u32, height: u32) -> Self {
+        Self {
+            id: next_id(),
+            path: String::from(path),
+            grid: Grid::filled(Colour::TRANSPARENT, width, height),
+        }
+    }
+
     pub fn load(path: &str) -> Result<Self, image::ImageError> {
         let image_buffer = image::open(path)?;
         let image = image_buffer.to_rgba();
@@ -22,14 +41,16 @@ impl Sprite {
                 let r = pixel[0];
                 let g = pixel[1];
                 let b = pixel[2];
+                let a = pixel[3];
 
-                data.push(Colour::new(r, g, b));
+                data.push(Colour::rgba(r, g, b, a));
             }
         }
 
         let grid = Grid::from_slice(&data, width, height);
 
         let sprite = Sprite {
+            id: next_id(),
             path: String::from(path),
             grid,
         };
@@ -37,6 +58,10 @@ impl Sprite {
         Ok(sprite)
     }
 
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
     pub fn path(&self) -> &str {
         &self.path
     }
@@ -64,4 +89,27 @@ impl Sprite {
     pub fn data(&self) -> &[Colour] {
         self.grid.data()
     }
+
+    pub fn set_at(&mut self, x: i32, y: i32, colour: Colour) {
+        if let Some(pixel) = self.grid.get_at_mut(x, y) {
+            *pixel = colour;
+        }
+    }
+
+    // Changes the size of the sprite, keeping the pixels that still fit
+    // at the same coordinates.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        let mut grid = Grid::filled(Colour::TRANSPARENT, width, height);
+        for y in 0..self.height().min(height) as i32 {
+            for x in 0..self.width().min(width) as i32 {
+                *grid.get_at_mut(x, y).unwrap() = *self.grid.get_at(x, y).unwrap();
+            }
+        }
+
+        self.grid = grid;
+    }
+}
+
+fn next_id() -> u64 {
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
 }
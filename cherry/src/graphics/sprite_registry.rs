@@ -0,0 +1,33 @@
+use super::sprite::Sprite;
+
+// A handle to a sprite held by a registry.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SpriteId(usize);
+
+#[derive(Default)]
+pub struct SpriteRegistry {
+    sprites: Vec<Sprite>,
+}
+
+impl SpriteRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, sprite: Sprite) -> SpriteId {
+        self.sprites.push(sprite);
+        SpriteId(self.sprites.len() - 1)
+    }
+
+    pub fn get(&self, id: SpriteId) -> &Sprite {
+        &self.sprites[id.0]
+    }
+
+    pub fn len(&self) -> usize {
+        self.sprites.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sprites.is_empty()
+    }
+}
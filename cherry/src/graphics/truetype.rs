@@ -0,0 +1,80 @@
+use fontdue::FontSettings;
+
+use super::{
+    colour::Colour,
+    font::FontError,
+    sprite::Sprite,
+};
+
+pub(crate) struct TrueType {
+    font: fontdue::Font,
+    size: f32,
+    baseline: f32,
+}
+
+impl TrueType {
+    pub(crate) fn load(path: &str, size: f32) -> Result<Self, FontError> {
+        let data = std::fs::read(path)?;
+        let settings = FontSettings {
+            scale: size,
+            ..FontSettings::default()
+        };
+
+        let font = fontdue::Font::from_bytes(data, settings)
+            .map_err(|error| FontError::TrueType(String::from(error)))?;
+        let baseline = font
+            .horizontal_line_metrics(size)
+            .map(|metrics| metrics.ascent)
+            .unwrap_or(size);
+
+        Ok(Self {
+            font,
+            size,
+            baseline,
+        })
+    }
+
+    // The cell is wide enough for the widest of the printable ASCII
+    // characters, and tall enough for a whole line.
+    pub(crate) fn cell_size(&self) -> (u32, u32) {
+        let width = (' '..='~')
+            .map(|c| self.font.metrics(c, self.size).advance_width)
+            .fold(0.0, f32::max);
+        let height = self
+            .font
+            .horizontal_line_metrics(self.size)
+            .map(|metrics| metrics.ascent - metrics.descent)
+            .unwrap_or(self.size);
+
+        (width.ceil().max(1.0) as u32, height.ceil().max(1.0) as u32)
+    }
+
+    pub(crate) fn has_glyph(&self, c: char) -> bool {
+        self.font.lookup_glyph_index(c) != 0
+    }
+
+    // Draws the glyph into the cell at `x` and `y` of the sprite. The glyph
+    // sits on the font's baseline, and anything outside the cell is cut off.
+    pub(crate) fn rasterize(&self, c: char, sprite: &mut Sprite, cell: (i32, i32, u32, u32)) {
+        let (x, y, width, height) = cell;
+        let (metrics, coverage) = self.font.rasterize(c, self.size);
+
+        let left = metrics.xmin;
+        let top = self.baseline.round() as i32 - metrics.ymin - metrics.height as i32;
+
+        for row in 0..metrics.height {
+            for column in 0..metrics.width {
+                let cx = left + column as i32;
+                let cy = top + row as i32;
+                if cx < 0 || cy < 0 || cx >= width as i32 || cy >= height as i32 {
+                    continue;
+                }
+
+                // The coverage works both as a mask and as the alpha of a
+                // white glyph.
+                let value = coverage[column + row * metrics.width];
+                sprite.set_at(x + cx, y + cy, Colour::rgba(value, value, value, value));
+            }
+        }
+    }
+}
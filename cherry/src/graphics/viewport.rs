@@ -0,0 +1,83 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+// How the console is fitted to a window of a different size.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScaleMode {
+    // Scale by the largest whole number that fits, so that every pixel of
+    // the console is the same size, and leave a border around the rest.
+    #[default]
+    Integer,
+    // Scale as much as fits while keeping the aspect ratio.
+    Fit,
+    // Fill the window, whatever the aspect ratio.
+    Stretch,
+    // Keep the scale and add columns and rows to fill the window.
+    Grow,
+}
+
+// Where the console is drawn in the window, in window pixels from the
+// top-left.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Viewport {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    console: (u32, u32),
+}
+
+impl Viewport {
+    pub fn new(mode: ScaleMode, console: (u32, u32), window: (u32, u32)) -> Self {
+        let (cw, ch) = (console.0.max(1), console.1.max(1));
+        let (ww, wh) = window;
+
+        let (width, height) = match mode {
+            ScaleMode::Integer => {
+                let scale = (ww / cw).min(wh / ch).max(1);
+                (cw * scale, ch * scale)
+            }
+            ScaleMode::Fit => {
+                let scale = (ww as f32 / cw as f32).min(wh as f32 / ch as f32);
+                (
+                    (cw as f32 * scale).round() as u32,
+                    (ch as f32 * scale).round() as u32,
+                )
+            }
+            ScaleMode::Stretch => (ww, wh),
+            // The console has already grown to fit, so only the pixels left
+            // over from a partial cell remain.
+            ScaleMode::Grow => (cw, ch),
+        };
+
+        // Centre the console. It may be cropped when the window is smaller
+        // than it at a scale of one.
+        Self {
+            x: (ww as i32 - width as i32) / 2,
+            y: (wh as i32 - height as i32) / 2,
+            width: width.max(1),
+            height: height.max(1),
+            console: (cw, ch),
+        }
+    }
+
+    // Converts a position in the window to a position on the console, in
+    // the console's pixels.
+    pub fn to_console(&self, x: i32, y: i32) -> (i32, i32) {
+        let x = (x - self.x) as f32 * self.console.0 as f32 / self.width as f32;
+        let y = (y - self.y) as f32 * self.console.1 as f32 / self.height as f32;
+
+        (x.floor() as i32, y.floor() as i32)
+    }
+
+    // Converts a position on the console to the middle of the window pixels
+    // it is drawn to.
+    pub fn to_window(&self, x: i32, y: i32) -> (i32, i32) {
+        let x = (x as f32 + 0.5) * self.width as f32 / self.console.0 as f32;
+        let y = (y as f32 + 0.5) * self.height as f32 / self.console.1 as f32;
+
+        (self.x + x.floor() as i32, self.y + y.floor() as i32)
+    }
+}
@@ -1,2 +1,8 @@
+pub mod action;
 pub mod button;
+pub mod gamepad;
 pub mod key;
+pub mod modifiers;
+pub mod mouse;
+pub mod recording;
+pub(crate) mod switches;
@@ -0,0 +1,144 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    fs::File,
+    io::{
+        BufReader,
+        BufWriter,
+    },
+};
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::{
+    button::Button,
+    gamepad::GamepadButton,
+    key::Key,
+    modifiers::Modifiers,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Binding {
+    // A key chord only triggers while exactly its modifiers are held, so
+    // that a plain binding is blocked by any extra modifier. A modifier key
+    // bound on its own does not count as one of its own modifiers.
+    Key {
+        key: Key,
+        #[serde(default)]
+        modifiers: Modifiers,
+    },
+    Button {
+        button: Button,
+    },
+    Gamepad {
+        button: GamepadButton,
+    },
+}
+
+impl Binding {
+    pub fn key(key: Key) -> Self {
+        Binding::Key {
+            key,
+            modifiers: Modifiers::none(),
+        }
+    }
+
+    pub fn chord(key: Key, modifiers: Modifiers) -> Self {
+        Binding::Key { key, modifiers }
+    }
+
+    pub fn button(button: Button) -> Self {
+        Binding::Button { button }
+    }
+
+    pub fn gamepad(button: GamepadButton) -> Self {
+        Binding::Gamepad { button }
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Bindings {
+    actions: BTreeMap<String, Vec<Binding>>,
+}
+
+impl Bindings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn load(path: &str) -> Result<Self, BindingsError> {
+        let file = File::open(path)?;
+        let bindings = serde_json::from_reader(BufReader::new(file))?;
+
+        Ok(bindings)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), BindingsError> {
+        let file = File::create(path)?;
+        serde_json::to_writer_pretty(BufWriter::new(file), self)?;
+
+        Ok(())
+    }
+
+    pub fn bind(&mut self, action: &str, binding: Binding) {
+        let bindings = self.actions.entry(String::from(action)).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    pub fn unbind(&mut self, action: &str, binding: Binding) {
+        if let Some(bindings) = self.actions.get_mut(action) {
+            bindings.retain(|other| *other != binding);
+        }
+    }
+
+    pub fn clear(&mut self, action: &str) {
+        self.actions.remove(action);
+    }
+
+    pub fn get(&self, action: &str) -> &[Binding] {
+        self.actions
+            .get(action)
+            .map(|bindings| bindings.as_slice())
+            .unwrap_or(&[])
+    }
+
+    pub fn actions(&self) -> impl Iterator<Item = &str> {
+        self.actions.keys().map(|action| action.as_str())
+    }
+}
+
+#[derive(Debug)]
+pub enum BindingsError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for BindingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BindingsError::Io(error) => write!(f, "Failed to access bindings: {}", error),
+            BindingsError::Json(error) => write!(f, "Failed to parse bindings: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for BindingsError {}
+
+impl From<std::io::Error> for BindingsError {
+    fn from(error: std::io::Error) -> Self {
+        BindingsError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for BindingsError {
+    fn from(error: serde_json::Error) -> Self {
+        BindingsError::Json(error)
+    }
+}
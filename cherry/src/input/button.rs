@@ -1,8 +1,15 @@
-#[derive(Debug, Copy, Clone)]
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Button {
     Left,
     Right,
     Middle,
+    X1,
+    X2,
 
     // Ensure this is the last item
     // in the list.
@@ -0,0 +1,72 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::switches::Switches;
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GamepadButton {
+    A,
+    B,
+    X,
+    Y,
+    Back,
+    Guide,
+    Start,
+    LeftStick,
+    RightStick,
+    LeftShoulder,
+    RightShoulder,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+
+    // Ensure this is the last item
+    // in the list.
+    Unknown,
+}
+
+impl GamepadButton {
+    pub const fn count() -> usize {
+        Self::Unknown as usize
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GamepadAxis {
+    LeftX,
+    LeftY,
+    RightX,
+    RightY,
+    TriggerLeft,
+    TriggerRight,
+
+    // Ensure this is the last item
+    // in the list.
+    Unknown,
+}
+
+impl GamepadAxis {
+    pub const fn count() -> usize {
+        Self::Unknown as usize
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Gamepad {
+    pub id: u32,
+    pub buttons: Switches,
+    pub axes: [f32; GamepadAxis::count() + 1],
+}
+
+impl Gamepad {
+    pub fn new(id: u32) -> Self {
+        Self {
+            id,
+            buttons: Switches::new(GamepadButton::count() + 1),
+            axes: [0.0; GamepadAxis::count() + 1],
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::key::Key;
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub gui: bool,
+}
+
+impl Modifiers {
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !(self.shift || self.ctrl || self.alt || self.gui)
+    }
+
+    // The modifiers without the one the key itself sets, if it is a
+    // modifier key.
+    pub fn without(mut self, key: Key) -> Self {
+        match key {
+            Key::LShift | Key::RShift => self.shift = false,
+            Key::LCtrl | Key::RCtrl => self.ctrl = false,
+            Key::LAlt | Key::RAlt => self.alt = false,
+            Key::LGui | Key::RGui => self.gui = false,
+            _ => {}
+        }
+
+        self
+    }
+}
@@ -0,0 +1,132 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::button::Button;
+
+// The longest gap between two presses that still counts as a double-click.
+const DOUBLE_CLICK_TIME: f32 = 0.4;
+
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Drag {
+    pub start: (i32, i32),
+    pub end: (i32, i32),
+    pub just_started: bool,
+    pub just_ended: bool,
+}
+
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize)]
+struct Tracker {
+    last_press: Option<(f32, (i32, i32))>,
+    double_click: bool,
+    drag: Option<DragState>,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+struct DragState {
+    start: (i32, i32),
+    end: (i32, i32),
+    held: bool,
+    moved: bool,
+    just_started: bool,
+    just_ended: bool,
+}
+
+// Tracks clicks and drags for every mouse button, in cell coordinates.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Mouse {
+    trackers: [Tracker; Button::count() + 1],
+}
+
+impl Mouse {
+    pub fn new() -> Self {
+        Self {
+            trackers: [Tracker::default(); Button::count() + 1],
+        }
+    }
+
+    pub fn press(&mut self, button: Button, cell: (i32, i32), time: f32) {
+        let tracker = &mut self.trackers[button as usize];
+
+        match tracker.last_press {
+            Some((last_time, last_cell))
+                if time - last_time <= DOUBLE_CLICK_TIME && last_cell == cell =>
+            {
+                // Start over, so that a third press is not another
+                // double-click.
+                tracker.double_click = true;
+                tracker.last_press = None;
+            }
+            _ => tracker.last_press = Some((time, cell)),
+        }
+
+        tracker.drag = Some(DragState {
+            start: cell,
+            end: cell,
+            held: true,
+            moved: false,
+            just_started: false,
+            just_ended: false,
+        });
+    }
+
+    pub fn release(&mut self, button: Button) {
+        let tracker = &mut self.trackers[button as usize];
+
+        if let Some(drag) = &mut tracker.drag {
+            if drag.moved {
+                drag.held = false;
+                drag.just_ended = true;
+            } else {
+                tracker.drag = None;
+            }
+        }
+    }
+
+    pub fn moved(&mut self, cell: (i32, i32)) {
+        for tracker in self.trackers.iter_mut() {
+            if let Some(drag) = &mut tracker.drag {
+                if !drag.held {
+                    continue;
+                }
+
+                drag.end = cell;
+                if !drag.moved && drag.end != drag.start {
+                    drag.moved = true;
+                    drag.just_started = true;
+                }
+            }
+        }
+    }
+
+    pub fn double_click(&self, button: Button) -> bool {
+        self.trackers[button as usize].double_click
+    }
+
+    pub fn drag(&self, button: Button) -> Option<Drag> {
+        self.trackers[button as usize]
+            .drag
+            .filter(|drag| drag.moved)
+            .map(|drag| Drag {
+                start: drag.start,
+                end: drag.end,
+                just_started: drag.just_started,
+                just_ended: drag.just_ended,
+            })
+    }
+
+    pub fn end_update(&mut self) {
+        for tracker in self.trackers.iter_mut() {
+            tracker.double_click = false;
+
+            if let Some(drag) = &mut tracker.drag {
+                if drag.just_ended {
+                    tracker.drag = None;
+                } else {
+                    drag.just_started = false;
+                }
+            }
+        }
+    }
+}
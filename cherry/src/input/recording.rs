@@ -0,0 +1,115 @@
+use std::{
+    fmt,
+    fs::File,
+    io::{
+        BufReader,
+        BufWriter,
+    },
+};
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    event::Event,
+    graphics::viewport::ScaleMode,
+    input::{
+        gamepad::Gamepad,
+        mouse::Mouse,
+        switches::Switches,
+    },
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    pub index: u64,
+    pub delta: f32,
+    pub events: Vec<Event>,
+}
+
+// The engine's timing and input at the start of the first recorded frame,
+// so that a replay starts from the same place as the recording did.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedState {
+    pub(crate) accumulator: f32,
+    pub(crate) elapsed: f32,
+    pub(crate) mouse_position: (i32, i32),
+    pub(crate) scroll: i32,
+    pub(crate) mouse: Mouse,
+    pub(crate) buttons: Switches,
+    pub(crate) keys: Switches,
+    pub(crate) scancodes: Switches,
+    pub(crate) gamepads: Vec<Gamepad>,
+    pub(crate) connected: Vec<u32>,
+    pub(crate) disconnected: Vec<u32>,
+    pub(crate) text_input: bool,
+    pub(crate) text: String,
+    pub(crate) resized: Option<(u32, u32)>,
+    // Mouse positions are mapped from the window to the console, so the
+    // replay needs the same window.
+    pub(crate) window_size: (u32, u32),
+    pub(crate) scale_mode: ScaleMode,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recording {
+    pub update_rate: u32,
+    #[serde(default)]
+    pub start: Option<RecordedState>,
+    pub frames: Vec<RecordedFrame>,
+}
+
+impl Recording {
+    pub fn new(update_rate: u32) -> Self {
+        Self {
+            update_rate,
+            start: None,
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Self, RecordingError> {
+        let file = File::open(path)?;
+        let recording = serde_json::from_reader(BufReader::new(file))?;
+
+        Ok(recording)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), RecordingError> {
+        let file = File::create(path)?;
+        serde_json::to_writer(BufWriter::new(file), self)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum RecordingError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordingError::Io(error) => write!(f, "Failed to access recording: {}", error),
+            RecordingError::Json(error) => write!(f, "Failed to parse recording: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for RecordingError {}
+
+impl From<std::io::Error> for RecordingError {
+    fn from(error: std::io::Error) -> Self {
+        RecordingError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for RecordingError {
+    fn from(error: serde_json::Error) -> Self {
+        RecordingError::Json(error)
+    }
+}
@@ -0,0 +1,56 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+// Tracks the up/down state of a set of buttons or keys across updates.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Switches {
+    last_update: Vec<bool>,
+    this_update: Vec<bool>,
+    released: Vec<bool>,
+}
+
+impl Switches {
+    pub fn new(count: usize) -> Self {
+        Self {
+            last_update: vec![false; count],
+            this_update: vec![false; count],
+            released: vec![false; count],
+        }
+    }
+
+    pub fn press(&mut self, index: usize) {
+        self.this_update[index] = true;
+        self.released[index] = false;
+    }
+
+    pub fn release(&mut self, index: usize) {
+        // A press that no update has seen yet is released after the next
+        // update instead, so that short taps are never lost.
+        if self.last_update[index] {
+            self.this_update[index] = false;
+        } else {
+            self.released[index] = true;
+        }
+    }
+
+    pub fn is_down(&self, index: usize) -> bool {
+        self.this_update[index]
+    }
+
+    pub fn was_down(&self, index: usize) -> bool {
+        self.last_update[index]
+    }
+
+    pub fn end_update(&mut self) {
+        self.last_update.copy_from_slice(&self.this_update);
+
+        for (down, released) in self.this_update.iter_mut().zip(self.released.iter_mut()) {
+            if *released {
+                *down = false;
+                *released = false;
+            }
+        }
+    }
+}
@@ -1,12 +1,16 @@
+pub mod backend;
 pub mod engine;
 pub mod event;
 pub mod graphics;
 pub mod input;
+pub mod scene;
 pub mod terminal;
 pub mod window;
 
 use engine::Engine;
 
 pub trait Cherry {
-    fn on_update(&mut self, engine: &mut Engine);
+    fn on_update(&mut self, engine: &mut Engine, dt: f32);
+
+    fn on_render(&mut self, _engine: &mut Engine, _alpha: f32) {}
 }
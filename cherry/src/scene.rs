@@ -0,0 +1,131 @@
+use crate::{
+    engine::Engine,
+    Cherry,
+};
+
+pub enum Transition {
+    None,
+    Push(Box<dyn Scene>),
+    Pop,
+    Replace(Box<dyn Scene>),
+    Quit,
+}
+
+pub trait Scene {
+    fn on_enter(&mut self, _engine: &mut Engine) {}
+
+    fn on_exit(&mut self, _engine: &mut Engine) {}
+
+    fn on_pause(&mut self, _engine: &mut Engine) {}
+
+    fn on_resume(&mut self, _engine: &mut Engine) {}
+
+    fn on_update(&mut self, engine: &mut Engine, dt: f32) -> Transition;
+
+    fn on_render(&mut self, _engine: &mut Engine, _alpha: f32) {}
+
+    // Overlays are drawn on top of the scene below them, which stays
+    // paused underneath.
+    fn is_overlay(&self) -> bool {
+        false
+    }
+}
+
+pub struct SceneStack {
+    scenes: Vec<Box<dyn Scene>>,
+    pending: Vec<Box<dyn Scene>>,
+}
+
+impl SceneStack {
+    pub fn new(scene: Box<dyn Scene>) -> Self {
+        Self {
+            scenes: Vec::new(),
+            pending: vec![scene],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.scenes.len() + self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn push(&mut self, engine: &mut Engine, mut scene: Box<dyn Scene>) {
+        if let Some(top) = self.scenes.last_mut() {
+            top.on_pause(engine);
+        }
+
+        scene.on_enter(engine);
+        self.scenes.push(scene);
+    }
+
+    pub fn pop(&mut self, engine: &mut Engine) {
+        if let Some(mut scene) = self.scenes.pop() {
+            scene.on_exit(engine);
+        }
+
+        if let Some(top) = self.scenes.last_mut() {
+            top.on_resume(engine);
+        }
+    }
+
+    pub fn replace(&mut self, engine: &mut Engine, mut scene: Box<dyn Scene>) {
+        if let Some(mut top) = self.scenes.pop() {
+            top.on_exit(engine);
+        }
+
+        scene.on_enter(engine);
+        self.scenes.push(scene);
+    }
+
+    pub fn clear(&mut self, engine: &mut Engine) {
+        while let Some(mut scene) = self.scenes.pop() {
+            scene.on_exit(engine);
+        }
+    }
+
+    fn apply(&mut self, engine: &mut Engine, transition: Transition) {
+        match transition {
+            Transition::None => {}
+            Transition::Push(scene) => self.push(engine, scene),
+            Transition::Pop => self.pop(engine),
+            Transition::Replace(scene) => self.replace(engine, scene),
+            Transition::Quit => self.clear(engine),
+        }
+    }
+}
+
+impl Cherry for SceneStack {
+    fn on_update(&mut self, engine: &mut Engine, dt: f32) {
+        // The first scene is entered once the engine is available.
+        for scene in std::mem::take(&mut self.pending) {
+            self.push(engine, scene);
+        }
+
+        // Only the top scene updates; the ones below it are paused.
+        if let Some(top) = self.scenes.last_mut() {
+            let transition = top.on_update(engine, dt);
+            self.apply(engine, transition);
+        }
+
+        if self.scenes.is_empty() {
+            engine.quit();
+        }
+    }
+
+    fn on_render(&mut self, engine: &mut Engine, alpha: f32) {
+        // Draw from the topmost opaque scene upwards, so that overlays are
+        // drawn over the scenes they cover.
+        let first = self
+            .scenes
+            .iter()
+            .rposition(|scene| !scene.is_overlay())
+            .unwrap_or(0);
+
+        for scene in self.scenes[first..].iter_mut() {
+            scene.on_render(engine, alpha);
+        }
+    }
+}
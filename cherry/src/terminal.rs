@@ -1,2 +1,7 @@
 pub mod buffer;
+pub mod camera;
+pub mod console;
+pub mod cp437;
+pub mod layer;
+pub mod sprite_layer;
 pub mod tile;
@@ -0,0 +1,128 @@
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Camera {
+    // The world cell shown at the top-left of the viewport.
+    x: i32,
+    y: i32,
+    // The viewport, in cells of the layer being drawn to.
+    viewport_x: i32,
+    viewport_y: i32,
+    viewport_w: u32,
+    viewport_h: u32,
+    // The world area the viewport is kept inside.
+    bounds: Option<(i32, i32, u32, u32)>,
+}
+
+impl Camera {
+    pub fn new(viewport_x: i32, viewport_y: i32, viewport_w: u32, viewport_h: u32) -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            viewport_x,
+            viewport_y,
+            viewport_w,
+            viewport_h,
+            bounds: None,
+        }
+    }
+
+    pub fn position(&self) -> (i32, i32) {
+        (self.x, self.y)
+    }
+
+    pub fn set_position(&mut self, x: i32, y: i32) {
+        self.x = x;
+        self.y = y;
+        self.clamp();
+    }
+
+    pub fn scroll(&mut self, dx: i32, dy: i32) {
+        self.set_position(self.x + dx, self.y + dy);
+    }
+
+    pub fn centre_on(&mut self, x: i32, y: i32) {
+        let x = x - self.viewport_w as i32 / 2;
+        let y = y - self.viewport_h as i32 / 2;
+
+        self.set_position(x, y);
+    }
+
+    pub fn viewport(&self) -> (i32, i32, u32, u32) {
+        (
+            self.viewport_x,
+            self.viewport_y,
+            self.viewport_w,
+            self.viewport_h,
+        )
+    }
+
+    pub fn set_viewport(&mut self, x: i32, y: i32, w: u32, h: u32) {
+        self.viewport_x = x;
+        self.viewport_y = y;
+        self.viewport_w = w;
+        self.viewport_h = h;
+        self.clamp();
+    }
+
+    pub fn bounds(&self) -> Option<(i32, i32, u32, u32)> {
+        self.bounds
+    }
+
+    pub fn set_bounds(&mut self, bounds: Option<(i32, i32, u32, u32)>) {
+        self.bounds = bounds;
+        self.clamp();
+    }
+
+    // The area of the world that is visible through the viewport.
+    pub fn visible(&self) -> (i32, i32, u32, u32) {
+        (self.x, self.y, self.viewport_w, self.viewport_h)
+    }
+
+    pub fn is_visible(&self, x: i32, y: i32) -> bool {
+        x >= self.x
+            && y >= self.y
+            && x < self.x + self.viewport_w as i32
+            && y < self.y + self.viewport_h as i32
+    }
+
+    // Converts world coordinates into layer coordinates. Coordinates that
+    // fall outside of the viewport are clipped.
+    pub fn to_screen(&self, x: i32, y: i32) -> Option<(i32, i32)> {
+        if !self.is_visible(x, y) {
+            return None;
+        }
+
+        Some((x - self.x + self.viewport_x, y - self.y + self.viewport_y))
+    }
+
+    // Converts layer coordinates into world coordinates. Coordinates that
+    // fall outside of the viewport have no world position.
+    pub fn to_world(&self, x: i32, y: i32) -> Option<(i32, i32)> {
+        let x = x - self.viewport_x + self.x;
+        let y = y - self.viewport_y + self.y;
+
+        if !self.is_visible(x, y) {
+            return None;
+        }
+
+        Some((x, y))
+    }
+
+    fn clamp(&mut self) {
+        let (bx, by, bw, bh) = match self.bounds {
+            Some(bounds) => bounds,
+            None => return,
+        };
+
+        // A world smaller than the viewport is centred inside it.
+        self.x = clamp_axis(self.x, bx, bw, self.viewport_w);
+        self.y = clamp_axis(self.y, by, bh, self.viewport_h);
+    }
+}
+
+fn clamp_axis(position: i32, start: i32, length: u32, view: u32) -> i32 {
+    if length <= view {
+        start - (view - length) as i32 / 2
+    } else {
+        position.clamp(start, start + (length - view) as i32)
+    }
+}
@@ -0,0 +1,376 @@
+use crate::graphics::colour::{
+    BlendMode,
+    Colour,
+};
+
+use super::{
+    buffer::Buffer,
+    camera::Camera,
+    tile::Tile,
+};
+
+pub struct Console {
+    tiles: Buffer,
+    fg: Colour,
+    bg: Colour,
+    transparent_glyph: bool,
+    transparent_bg: bool,
+    blend_mode: BlendMode,
+    camera: Option<Camera>,
+}
+
+impl Console {
+    pub fn new(columns: u32, rows: u32) -> Self {
+        Self {
+            tiles: Buffer::filled(Tile::default(), columns, rows),
+            fg: Colour::WHITE,
+            bg: Colour::BLACK,
+            transparent_glyph: false,
+            transparent_bg: false,
+            blend_mode: BlendMode::Alpha,
+            camera: None,
+        }
+    }
+
+    pub fn columns(&self) -> u32 {
+        self.tiles.columns()
+    }
+
+    pub fn rows(&self) -> u32 {
+        self.tiles.rows()
+    }
+
+    pub fn tiles(&self) -> &Buffer {
+        &self.tiles
+    }
+
+    pub fn tiles_mut(&mut self) -> &mut Buffer {
+        &mut self.tiles
+    }
+
+    pub fn clear(&mut self) {
+        for tile in self.tiles.data_mut().iter_mut() {
+            *tile = Tile {
+                bg: self.bg,
+                ..Tile::default()
+            };
+        }
+    }
+
+    pub fn set_fg(&mut self, fg: Colour) {
+        self.fg = fg;
+    }
+
+    pub fn set_bg(&mut self, bg: Colour) {
+        self.bg = bg;
+    }
+
+    pub fn set_transparent_glyph(&mut self, transparent: bool) {
+        self.transparent_glyph = transparent;
+    }
+
+    pub fn set_transparent_bg(&mut self, transparent: bool) {
+        self.transparent_bg = transparent;
+    }
+
+    pub fn set_blend_mode(&mut self, mode: BlendMode) {
+        self.blend_mode = mode;
+    }
+
+    pub fn camera(&self) -> Option<&Camera> {
+        self.camera.as_ref()
+    }
+
+    // While a camera is set, drawing is in world coordinates and clipped to
+    // the camera's viewport.
+    pub fn set_camera(&mut self, camera: Option<Camera>) {
+        self.camera = camera;
+    }
+
+    fn canvas(&mut self) -> Canvas<'_> {
+        let pen = self.pen();
+        Canvas::new(&mut self.tiles, pen, self.blend_mode, self.camera.as_ref())
+    }
+
+    fn pen(&self) -> Tile {
+        Tile {
+            glyph: ' ',
+            fg: self.fg,
+            bg: self.bg,
+            transparent_glyph: self.transparent_glyph,
+            transparent_bg: self.transparent_bg,
+        }
+    }
+
+    pub fn draw(&mut self, x: i32, y: i32, c: char) {
+        self.canvas().draw(x, y, c);
+    }
+
+    pub fn draw_h_line(&mut self, x: i32, y: i32, w: i32, c: char) {
+        self.canvas().draw_h_line(x, y, w, c);
+    }
+
+    pub fn draw_str(&mut self, x: i32, y: i32, str: &str) {
+        self.canvas().draw_str(x, y, str);
+    }
+
+    pub fn draw_border(&mut self, x: i32, y: i32, w: i32, h: i32) {
+        self.canvas().draw_border(x, y, w, h);
+    }
+
+    pub fn fill_rect(&mut self, x: i32, y: i32, w: i32, h: i32) {
+        self.canvas().fill_rect(x, y, w, h);
+    }
+
+    // The camera is the destination's. With one, (x, y) is a world cell.
+    pub fn blit(&self, dest: &mut Buffer, camera: Option<&Camera>, x: i32, y: i32, blit: &Blit) {
+        blit_buffer(&self.tiles, dest, camera, x, y, blit);
+    }
+}
+
+pub struct Blit {
+    source: Option<(i32, i32, u32, u32)>,
+    fg_alpha: f32,
+    bg_alpha: f32,
+    key: Option<Colour>,
+}
+
+impl Blit {
+    pub fn new() -> Self {
+        Self {
+            source: None,
+            fg_alpha: 1.0,
+            bg_alpha: 1.0,
+            key: None,
+        }
+    }
+
+    // Copy only part of the source. By default the whole source is copied.
+    pub fn with_source(mut self, x: i32, y: i32, w: u32, h: u32) -> Self {
+        self.source = Some((x, y, w, h));
+        self
+    }
+
+    pub fn with_alpha(mut self, fg_alpha: f32, bg_alpha: f32) -> Self {
+        self.fg_alpha = fg_alpha.clamp(0.0, 1.0);
+        self.bg_alpha = bg_alpha.clamp(0.0, 1.0);
+        self
+    }
+
+    // Source tiles with this background colour are not copied.
+    pub fn with_key(mut self, key: Colour) -> Self {
+        self.key = Some(key);
+        self
+    }
+}
+
+impl Default for Blit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn blit_buffer(
+    source: &Buffer,
+    dest: &mut Buffer,
+    camera: Option<&Camera>,
+    x: i32,
+    y: i32,
+    blit: &Blit,
+) {
+    let (sx, sy, w, h) = blit
+        .source
+        .unwrap_or((0, 0, source.columns(), source.rows()));
+
+    for r in 0..h as i32 {
+        for c in 0..w as i32 {
+            let above = match source.get_at(sx + c, sy + r) {
+                Some(tile) => tile,
+                None => continue,
+            };
+
+            if blit.key == Some(above.bg) && !above.transparent_bg {
+                continue;
+            }
+
+            // Like drawing, tiles outside of the camera's viewport are clipped.
+            let (dx, dy) = match camera {
+                Some(camera) => match camera.to_screen(x + c, y + r) {
+                    Some(position) => position,
+                    None => continue,
+                },
+                None => (x + c, y + r),
+            };
+
+            if let Some(below) = dest.get_at_mut(dx, dy) {
+                *below = blend(below, above, blit.fg_alpha, blit.bg_alpha);
+            }
+        }
+    }
+}
+
+fn blend(below: &Tile, above: &Tile, fg_alpha: f32, bg_alpha: f32) -> Tile {
+    // Transparent parts of the source leave the destination as it is.
+    let fg_alpha = if above.transparent_glyph {
+        0.0
+    } else {
+        fg_alpha
+    };
+    let bg_alpha = if above.transparent_bg { 0.0 } else { bg_alpha };
+
+    let mut tile = *below;
+    if bg_alpha > 0.0 {
+        tile.bg = below.bg.lerp(above.bg, bg_alpha);
+        tile.transparent_bg = false;
+    }
+
+    if fg_alpha <= 0.0 {
+        return tile;
+    }
+
+    if fg_alpha >= 1.0 {
+        tile.glyph = above.glyph;
+        tile.fg = above.fg;
+    } else if above.glyph == ' ' {
+        // An empty source glyph fades the destination glyph towards the
+        // source background.
+        tile.fg = below.fg.lerp(above.bg, bg_alpha);
+    } else if below.glyph == ' ' || below.transparent_glyph {
+        tile.glyph = above.glyph;
+        tile.fg = tile.bg.lerp(above.fg, fg_alpha);
+    } else if below.glyph == above.glyph {
+        tile.fg = below.fg.lerp(above.fg, fg_alpha);
+    } else if fg_alpha < 0.5 {
+        // Fade the destination glyph out before the source glyph fades in.
+        tile.fg = below.fg.lerp(tile.bg, fg_alpha * 2.0);
+    } else {
+        tile.glyph = above.glyph;
+        tile.fg = tile.bg.lerp(above.fg, (fg_alpha - 0.5) * 2.0);
+    }
+
+    tile.transparent_glyph = false;
+    tile
+}
+
+// Draws with a pen onto a buffer, optionally through a camera.
+pub(crate) struct Canvas<'a> {
+    buffer: &'a mut Buffer,
+    pen: Tile,
+    mode: BlendMode,
+    camera: Option<&'a Camera>,
+}
+
+impl<'a> Canvas<'a> {
+    pub(crate) fn new(
+        buffer: &'a mut Buffer,
+        pen: Tile,
+        mode: BlendMode,
+        camera: Option<&'a Camera>,
+    ) -> Self {
+        Self {
+            buffer,
+            pen,
+            mode,
+            camera,
+        }
+    }
+
+    pub(crate) fn draw(&mut self, x: i32, y: i32, c: char) {
+        let (x, y) = match self.camera {
+            Some(camera) => match camera.to_screen(x, y) {
+                Some(position) => position,
+                None => return,
+            },
+            None => (x, y),
+        };
+
+        // Out-of-bounds coordinates are ignored.
+        let tile = match self.buffer.get_at_mut(x, y) {
+            Some(tile) => tile,
+            None => return,
+        };
+
+        // Transparent parts of the pen leave the tile as it is. The parts
+        // of the tile that were transparent have nothing to blend with.
+        let pen = &self.pen;
+        if !pen.transparent_glyph {
+            let below = if tile.transparent_glyph {
+                Colour::TRANSPARENT
+            } else {
+                tile.fg
+            };
+
+            tile.glyph = c;
+            tile.fg = below.blend(pen.fg, self.mode);
+            tile.transparent_glyph = false;
+        }
+
+        if !pen.transparent_bg {
+            let below = if tile.transparent_bg {
+                Colour::TRANSPARENT
+            } else {
+                tile.bg
+            };
+
+            tile.bg = below.blend(pen.bg, self.mode);
+            tile.transparent_bg = false;
+        }
+    }
+
+    pub(crate) fn draw_h_line(&mut self, x: i32, y: i32, w: i32, c: char) {
+        let x0 = x;
+        let x1 = x + w;
+
+        for x in x0..x1 {
+            self.draw(x, y, c);
+        }
+    }
+
+    pub(crate) fn draw_str(&mut self, x: i32, y: i32, str: &str) {
+        for (i, c) in str.chars().enumerate() {
+            self.draw(x + i as i32, y, c);
+        }
+    }
+
+    pub(crate) fn draw_border(&mut self, x: i32, y: i32, w: i32, h: i32) {
+        const TL: char = '┌';
+        const TR: char = '┐';
+        const BL: char = '└';
+        const BR: char = '┘';
+        const VT: char = '│';
+        const HT: char = '─';
+
+        let x0 = x;
+        let x1 = x + w - 1;
+        let y0 = y;
+        let y1 = y + h - 1;
+
+        self.draw(x0, y0, TL);
+        self.draw(x1, y0, TR);
+        self.draw(x1, y1, BR);
+        self.draw(x0, y1, BL);
+
+        for x in x0 + 1..=x1 - 1 {
+            self.draw(x, y0, HT);
+            self.draw(x, y1, HT);
+        }
+
+        for y in y0 + 1..=y1 - 1 {
+            self.draw(x0, y, VT);
+            self.draw(x1, y, VT);
+        }
+    }
+
+    pub(crate) fn fill_rect(&mut self, x: i32, y: i32, w: i32, h: i32) {
+        let x0 = x;
+        let x1 = x + w - 1;
+        let y0 = y;
+        let y1 = y + h - 1;
+
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                self.draw(x, y, ' ');
+            }
+        }
+    }
+}
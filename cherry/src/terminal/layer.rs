@@ -0,0 +1,106 @@
+use crate::graphics::font_registry::FontId;
+
+use super::{
+    buffer::Buffer,
+    tile::Tile,
+};
+
+pub const MAIN_LAYER: &str = "main";
+
+pub struct Layer {
+    name: String,
+    x: i32,
+    y: i32,
+    z: i32,
+    visible: bool,
+    tiles: Buffer,
+    font: Option<FontId>,
+}
+
+impl Layer {
+    pub fn new(name: &str, columns: u32, rows: u32) -> Self {
+        Self {
+            name: String::from(name),
+            x: 0,
+            y: 0,
+            z: 0,
+            visible: true,
+            tiles: Buffer::filled(Tile::transparent(), columns, rows),
+            font: None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    // The offset is measured in cells of the engine's font.
+    pub fn offset(&self) -> (i32, i32) {
+        (self.x, self.y)
+    }
+
+    pub fn set_offset(&mut self, x: i32, y: i32) {
+        self.x = x;
+        self.y = y;
+    }
+
+    pub fn z(&self) -> i32 {
+        self.z
+    }
+
+    pub fn set_z(&mut self, z: i32) {
+        self.z = z;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    pub fn columns(&self) -> u32 {
+        self.tiles.columns()
+    }
+
+    pub fn rows(&self) -> u32 {
+        self.tiles.rows()
+    }
+
+    // Changes the size of the layer, keeping the tiles that still fit at
+    // the same cells. New cells are filled with the given tile.
+    pub fn resize(&mut self, columns: u32, rows: u32, fill: Tile) {
+        let mut tiles = Buffer::filled(fill, columns, rows);
+        for y in 0..self.rows().min(rows) as i32 {
+            for x in 0..self.columns().min(columns) as i32 {
+                *tiles.get_at_mut(x, y).unwrap() = *self.tiles.get_at(x, y).unwrap();
+            }
+        }
+
+        self.tiles = tiles;
+    }
+
+    pub fn tiles(&self) -> &Buffer {
+        &self.tiles
+    }
+
+    pub fn tiles_mut(&mut self) -> &mut Buffer {
+        &mut self.tiles
+    }
+
+    // A layer without a font of its own uses the engine's font.
+    pub fn font(&self) -> Option<FontId> {
+        self.font
+    }
+
+    pub fn set_font(&mut self, font: Option<FontId>) {
+        self.font = font;
+    }
+
+    pub fn clear(&mut self) {
+        for tile in self.tiles.data_mut().iter_mut() {
+            *tile = Tile::transparent();
+        }
+    }
+}
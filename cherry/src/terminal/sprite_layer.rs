@@ -0,0 +1,200 @@
+use std::collections::BTreeMap;
+
+use blueberry::Vec2f;
+
+use crate::graphics::{
+    colour::Colour,
+    font_registry::FontId,
+    sprite_registry::SpriteId,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EntityId(u32);
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum EntityImage {
+    // A glyph of a font, or of the engine's font when there is none.
+    Glyph {
+        font: Option<FontId>,
+        glyph: char,
+    },
+    // A region of a sprite, in pixels.
+    Region {
+        sprite: SpriteId,
+        x: u32,
+        y: u32,
+        w: u32,
+        h: u32,
+    },
+}
+
+// Something drawn over the tiles at any position, rather than in a cell.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Entity {
+    pub image: EntityImage,
+    // The top-left of the image, in cells of the engine's font. While the
+    // engine has a camera, these are world cells seen through it.
+    pub x: f32,
+    pub y: f32,
+    pub z: i32,
+    pub scale: Vec2f,
+    // Clockwise, in radians, about the centre of the image.
+    pub rotation: f32,
+    pub tint: Colour,
+}
+
+impl Entity {
+    pub fn glyph(x: f32, y: f32, glyph: char) -> Self {
+        Self::new(x, y, EntityImage::Glyph { font: None, glyph })
+    }
+
+    pub fn region(x: f32, y: f32, sprite: SpriteId, source: (u32, u32, u32, u32)) -> Self {
+        let (sx, sy, w, h) = source;
+        let image = EntityImage::Region {
+            sprite,
+            x: sx,
+            y: sy,
+            w,
+            h,
+        };
+
+        Self::new(x, y, image)
+    }
+
+    fn new(x: f32, y: f32, image: EntityImage) -> Self {
+        Self {
+            image,
+            x,
+            y,
+            z: 0,
+            scale: Vec2f::one(),
+            rotation: 0.0,
+            tint: Colour::WHITE,
+        }
+    }
+
+    // Only glyphs have a font.
+    pub fn with_font(mut self, font: FontId) -> Self {
+        if let EntityImage::Glyph { glyph, .. } = self.image {
+            self.image = EntityImage::Glyph {
+                font: Some(font),
+                glyph,
+            };
+        }
+
+        self
+    }
+
+    pub fn with_z(mut self, z: i32) -> Self {
+        self.z = z;
+        self
+    }
+
+    pub fn with_scale(mut self, x: f32, y: f32) -> Self {
+        self.scale = Vec2f::new(x, y);
+        self
+    }
+
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    // Glyphs are drawn in the tint. Sprites and colour glyphs are
+    // multiplied by it.
+    pub fn with_tint(mut self, tint: Colour) -> Self {
+        self.tint = tint;
+        self
+    }
+
+    pub fn set_position(&mut self, x: f32, y: f32) {
+        self.x = x;
+        self.y = y;
+    }
+}
+
+// The entities drawn on top of every tile layer. Unlike tiles, entities
+// stay where they are until they are moved or removed.
+pub struct SpriteLayer {
+    entities: BTreeMap<EntityId, Entity>,
+    next_id: u32,
+    x: f32,
+    y: f32,
+    visible: bool,
+}
+
+impl SpriteLayer {
+    pub fn new() -> Self {
+        Self {
+            entities: BTreeMap::new(),
+            next_id: 0,
+            x: 0.0,
+            y: 0.0,
+            visible: true,
+        }
+    }
+
+    pub fn add(&mut self, entity: Entity) -> EntityId {
+        let id = EntityId(self.next_id);
+        self.next_id += 1;
+        self.entities.insert(id, entity);
+        id
+    }
+
+    pub fn remove(&mut self, id: EntityId) -> Option<Entity> {
+        self.entities.remove(&id)
+    }
+
+    pub fn get(&self, id: EntityId) -> Option<&Entity> {
+        self.entities.get(&id)
+    }
+
+    pub fn get_mut(&mut self, id: EntityId) -> Option<&mut Entity> {
+        self.entities.get_mut(&id)
+    }
+
+    pub fn clear(&mut self) {
+        self.entities.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    // Moves every entity at once, on top of the engine's camera. The offset
+    // is measured in cells of the engine's font.
+    pub fn offset(&self) -> (f32, f32) {
+        (self.x, self.y)
+    }
+
+    pub fn set_offset(&mut self, x: f32, y: f32) {
+        self.x = x;
+        self.y = y;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    // The entities from the bottom to the top. Entities with the same z
+    // keep the order in which they were added.
+    pub fn entities(&self) -> Vec<&Entity> {
+        let mut entities: Vec<&Entity> = self.entities.values().collect();
+        entities.sort_by_key(|entity| entity.z);
+        entities
+    }
+}
+
+impl Default for SpriteLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,16 +1,49 @@
-use crate::graphics::colour::Colour;
+use crate::graphics::colour::{
+    BlendMode,
+    Colour,
+};
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Tile {
     pub glyph: char,
     pub fg: Colour,
     pub bg: Colour,
+    pub transparent_glyph: bool,
+    pub transparent_bg: bool,
 }
 
 impl Tile {
     pub fn new() -> Self {
         Self::default()
     }
+
+    pub fn transparent() -> Self {
+        Self {
+            transparent_glyph: true,
+            transparent_bg: true,
+            ..Self::default()
+        }
+    }
+
+    // Places `above` over this tile. Transparent parts of `above` let the
+    // glyph or background of this tile show through, and translucent
+    // colours are blended with the colours of this tile.
+    pub fn composite(&self, above: &Tile) -> Tile {
+        let mut tile = *self;
+
+        if !above.transparent_glyph && above.fg.a > 0 {
+            tile.glyph = above.glyph;
+            tile.fg = self.fg.blend(above.fg, BlendMode::Alpha);
+            tile.transparent_glyph = false;
+        }
+
+        if !above.transparent_bg && above.bg.a > 0 {
+            tile.bg = self.bg.blend(above.bg, BlendMode::Alpha);
+            tile.transparent_bg = false;
+        }
+
+        tile
+    }
 }
 
 impl Default for Tile {
@@ -19,6 +52,8 @@ impl Default for Tile {
             glyph: ' ',
             fg: Colour::WHITE,
             bg: Colour::BLACK,
+            transparent_glyph: false,
+            transparent_bg: false,
         }
     }
 }
@@ -1,30 +1,55 @@
+use std::{
+    collections::HashMap,
+    time::Duration,
+};
+
 use crate::{
     event::Event,
     input::{
         button::Button,
+        gamepad::{
+            GamepadAxis,
+            GamepadButton,
+        },
         key::Key,
     },
 };
 
 use sdl2::{
-    event::Event as SdlEvent,
-    keyboard::Keycode as SdlKeycode,
+    controller::{
+        Axis as SdlAxis,
+        Button as SdlControllerButton,
+        GameController as SdlGameController,
+    },
+    event::{
+        Event as SdlEvent,
+        WindowEvent as SdlWindowEvent,
+    },
+    keyboard::{
+        Keycode as SdlKeycode,
+        Scancode as SdlScancode,
+    },
+    mouse::MouseButton as SdlMouseButton,
     video::{
         GLContext,
         GLProfile,
         Window as SdlWindow,
     },
     EventPump as SdlEventPump,
+    GameControllerSubsystem as SdlGameControllerSubsystem,
     Sdl,
     VideoSubsystem as SdlVideo,
 };
 
 pub struct Window {
     events: SdlEventPump,
+    controller: SdlGameControllerSubsystem,
+    gamepads: HashMap<u32, SdlGameController>,
     window: SdlWindow,
     _sdl: Sdl,
-    _video: SdlVideo,
+    video: SdlVideo,
     _gl: GLContext,
+    damaged: bool,
 }
 
 impl Window {
@@ -39,6 +64,10 @@ impl Window {
             .event_pump()
             .expect("Failed to initialise the event subsystem.");
 
+        let controller = sdl
+            .game_controller()
+            .expect("Failed to initialise the game controller subsystem.");
+
         let gl_attributes = video.gl_attr();
         gl_attributes.set_context_profile(GLProfile::Core);
         gl_attributes.set_context_version(4, 5);
@@ -46,6 +75,7 @@ impl Window {
         let window = video
             .window(title, width, height)
             .position_centered()
+            .resizable()
             .opengl()
             .build()
             .expect("Failed to create window.");
@@ -57,12 +87,19 @@ impl Window {
             gl::Viewport(0, 0, width as i32, height as i32);
         }
 
+        // SDL starts with text input enabled, but we only want it while the
+        // client asks for it.
+        video.text_input().stop();
+
         Self {
             events,
+            controller,
+            gamepads: HashMap::new(),
             window,
             _sdl: sdl,
-            _video: video,
+            video,
             _gl: gl,
+            damaged: false,
         }
     }
 
@@ -70,55 +107,130 @@ impl Window {
         self.window
             .set_size(width, height)
             .expect("Failed to set window size.");
+    }
 
-        unsafe {
-            gl::Viewport(0, 0, width as i32, height as i32);
+    pub fn size(&self) -> (u32, u32) {
+        self.window.size()
+    }
+
+    // How long the display takes to refresh, or a sixtieth of a second when
+    // it does not say.
+    pub fn refresh_interval(&self) -> Duration {
+        let rate = self
+            .window
+            .display_mode()
+            .map(|mode| mode.refresh_rate)
+            .unwrap_or(0);
+        let rate = if rate > 0 { rate } else { 60 };
+
+        Duration::from_secs_f32(1.0 / rate as f32)
+    }
+
+    // Whether the contents of the window were lost since this was last
+    // asked, so that the frame has to be drawn again.
+    pub fn take_damaged(&mut self) -> bool {
+        std::mem::take(&mut self.damaged)
+    }
+
+    pub fn set_text_input(&mut self, enabled: bool) {
+        if enabled {
+            self.video.text_input().start();
+        } else {
+            self.video.text_input().stop();
         }
     }
 
     pub fn poll_event(&mut self) -> Option<Event> {
-        if let Some(event) = self.events.poll_event() {
-            match event {
-                SdlEvent::KeyDown { keycode, .. } => {
-                    if let Some(keycode) = keycode {
-                        let key = convert_keycode(keycode);
-                        Some(Event::KeyDown { key })
-                    } else {
-                        None
-                    }
+        // Skip over the events we do not handle rather than stopping at
+        // them, so that the rest of the queue is still processed this frame.
+        while let Some(event) = self.events.poll_event() {
+            let event = match event {
+                SdlEvent::KeyDown {
+                    keycode, scancode, ..
+                } => {
+                    let key = keycode.map(convert_keycode).unwrap_or(Key::Unknown);
+                    let scancode = scancode.map(convert_scancode).unwrap_or(Key::Unknown);
+                    Event::KeyDown { key, scancode }
                 }
-                SdlEvent::KeyUp { keycode, .. } => {
-                    if let Some(keycode) = keycode {
-                        let key = convert_keycode(keycode);
-                        Some(Event::KeyUp { key })
-                    } else {
-                        None
-                    }
+                SdlEvent::KeyUp {
+                    keycode, scancode, ..
+                } => {
+                    let key = keycode.map(convert_keycode).unwrap_or(Key::Unknown);
+                    let scancode = scancode.map(convert_scancode).unwrap_or(Key::Unknown);
+                    Event::KeyUp { key, scancode }
                 }
-                SdlEvent::MouseButtonDown { .. } => Some(Event::MouseButtonDown {
-                    button: Button::Left,
-                }),
-                SdlEvent::MouseButtonUp { .. } => Some(Event::MouseButtonUp {
-                    button: Button::Left,
-                }),
+                SdlEvent::TextInput { text, .. } => Event::TextInput { text },
+                SdlEvent::MouseButtonDown { mouse_btn, .. } => Event::MouseButtonDown {
+                    button: convert_button(mouse_btn),
+                },
+                SdlEvent::MouseButtonUp { mouse_btn, .. } => Event::MouseButtonUp {
+                    button: convert_button(mouse_btn),
+                },
                 SdlEvent::MouseMotion {
                     x, y, xrel, yrel, ..
-                } => Some(Event::MouseMove {
+                } => Event::MouseMove {
                     x,
                     y,
                     dx: xrel,
                     dy: yrel,
-                }),
+                },
                 SdlEvent::MouseWheel { y, .. } => {
                     let delta = y;
-                    Some(Event::MouseScroll { delta })
+                    Event::MouseScroll { delta }
                 }
-                SdlEvent::Quit { .. } => Some(Event::Quit),
-                _ => None,
-            }
-        } else {
-            None
+                SdlEvent::Window {
+                    win_event: SdlWindowEvent::SizeChanged(width, height),
+                    ..
+                } => Event::Resized {
+                    width: width as u32,
+                    height: height as u32,
+                },
+                SdlEvent::Window {
+                    win_event: SdlWindowEvent::Exposed | SdlWindowEvent::Restored,
+                    ..
+                } => {
+                    self.damaged = true;
+                    continue;
+                }
+                SdlEvent::ControllerDeviceAdded { which, .. } => {
+                    // Devices are added by index but identified by instance
+                    // ID in every other event.
+                    match self.controller.open(which) {
+                        Ok(gamepad) => {
+                            let id = gamepad.instance_id();
+                            self.gamepads.insert(id, gamepad);
+                            Event::GamepadConnected { id }
+                        }
+                        Err(_) => continue,
+                    }
+                }
+                SdlEvent::ControllerDeviceRemoved { which, .. } => {
+                    self.gamepads.remove(&which);
+                    Event::GamepadDisconnected { id: which }
+                }
+                SdlEvent::ControllerButtonDown { which, button, .. } => Event::GamepadButtonDown {
+                    id: which,
+                    button: convert_controller_button(button),
+                },
+                SdlEvent::ControllerButtonUp { which, button, .. } => Event::GamepadButtonUp {
+                    id: which,
+                    button: convert_controller_button(button),
+                },
+                SdlEvent::ControllerAxisMotion {
+                    which, axis, value, ..
+                } => Event::GamepadAxisMotion {
+                    id: which,
+                    axis: convert_axis(axis),
+                    value: (value as f32 / i16::MAX as f32).max(-1.0),
+                },
+                SdlEvent::Quit { .. } => Event::Quit,
+                _ => continue,
+            };
+
+            return Some(event);
         }
+
+        None
     }
 
     pub fn swap_buffers(&mut self) {
@@ -126,12 +238,266 @@ impl Window {
     }
 }
 
+fn convert_button(button: SdlMouseButton) -> Button {
+    match button {
+        SdlMouseButton::Left => Button::Left,
+        SdlMouseButton::Right => Button::Right,
+        SdlMouseButton::Middle => Button::Middle,
+        SdlMouseButton::X1 => Button::X1,
+        SdlMouseButton::X2 => Button::X2,
+        _ => Button::Unknown,
+    }
+}
+
+fn convert_controller_button(button: SdlControllerButton) -> GamepadButton {
+    match button {
+        SdlControllerButton::A => GamepadButton::A,
+        SdlControllerButton::B => GamepadButton::B,
+        SdlControllerButton::X => GamepadButton::X,
+        SdlControllerButton::Y => GamepadButton::Y,
+        SdlControllerButton::Back => GamepadButton::Back,
+        SdlControllerButton::Guide => GamepadButton::Guide,
+        SdlControllerButton::Start => GamepadButton::Start,
+        SdlControllerButton::LeftStick => GamepadButton::LeftStick,
+        SdlControllerButton::RightStick => GamepadButton::RightStick,
+        SdlControllerButton::LeftShoulder => GamepadButton::LeftShoulder,
+        SdlControllerButton::RightShoulder => GamepadButton::RightShoulder,
+        SdlControllerButton::DPadUp => GamepadButton::DPadUp,
+        SdlControllerButton::DPadDown => GamepadButton::DPadDown,
+        SdlControllerButton::DPadLeft => GamepadButton::DPadLeft,
+        SdlControllerButton::DPadRight => GamepadButton::DPadRight,
+    }
+}
+
+fn convert_axis(axis: SdlAxis) -> GamepadAxis {
+    match axis {
+        SdlAxis::LeftX => GamepadAxis::LeftX,
+        SdlAxis::LeftY => GamepadAxis::LeftY,
+        SdlAxis::RightX => GamepadAxis::RightX,
+        SdlAxis::RightY => GamepadAxis::RightY,
+        SdlAxis::TriggerLeft => GamepadAxis::TriggerLeft,
+        SdlAxis::TriggerRight => GamepadAxis::TriggerRight,
+    }
+}
+
 fn convert_keycode(keycode: SdlKeycode) -> Key {
     match keycode {
+        SdlKeycode::A => Key::A,
+        SdlKeycode::B => Key::B,
+        SdlKeycode::C => Key::C,
+        SdlKeycode::D => Key::D,
+        SdlKeycode::E => Key::E,
+        SdlKeycode::F => Key::F,
+        SdlKeycode::G => Key::G,
+        SdlKeycode::H => Key::H,
+        SdlKeycode::I => Key::I,
+        SdlKeycode::J => Key::J,
+        SdlKeycode::K => Key::K,
+        SdlKeycode::L => Key::L,
+        SdlKeycode::M => Key::M,
+        SdlKeycode::N => Key::N,
+        SdlKeycode::O => Key::O,
+        SdlKeycode::P => Key::P,
+        SdlKeycode::Q => Key::Q,
+        SdlKeycode::R => Key::R,
+        SdlKeycode::S => Key::S,
+        SdlKeycode::T => Key::T,
+        SdlKeycode::U => Key::U,
+        SdlKeycode::V => Key::V,
+        SdlKeycode::W => Key::W,
+        SdlKeycode::X => Key::X,
+        SdlKeycode::Y => Key::Y,
+        SdlKeycode::Z => Key::Z,
+        SdlKeycode::Num0 => Key::Num0,
+        SdlKeycode::Num1 => Key::Num1,
+        SdlKeycode::Num2 => Key::Num2,
+        SdlKeycode::Num3 => Key::Num3,
+        SdlKeycode::Num4 => Key::Num4,
+        SdlKeycode::Num5 => Key::Num5,
+        SdlKeycode::Num6 => Key::Num6,
+        SdlKeycode::Num7 => Key::Num7,
+        SdlKeycode::Num8 => Key::Num8,
+        SdlKeycode::Num9 => Key::Num9,
+        SdlKeycode::F1 => Key::F1,
+        SdlKeycode::F2 => Key::F2,
+        SdlKeycode::F3 => Key::F3,
+        SdlKeycode::F4 => Key::F4,
+        SdlKeycode::F5 => Key::F5,
+        SdlKeycode::F6 => Key::F6,
+        SdlKeycode::F7 => Key::F7,
+        SdlKeycode::F8 => Key::F8,
+        SdlKeycode::F9 => Key::F9,
+        SdlKeycode::F10 => Key::F10,
+        SdlKeycode::F11 => Key::F11,
+        SdlKeycode::F12 => Key::F12,
         SdlKeycode::Up => Key::Up,
         SdlKeycode::Down => Key::Down,
+        SdlKeycode::Left => Key::Left,
+        SdlKeycode::Right => Key::Right,
+        SdlKeycode::Insert => Key::Insert,
+        SdlKeycode::Delete => Key::Delete,
+        SdlKeycode::Home => Key::Home,
+        SdlKeycode::End => Key::End,
+        SdlKeycode::PageUp => Key::PageUp,
+        SdlKeycode::PageDown => Key::PageDown,
+        SdlKeycode::Escape => Key::Escape,
         SdlKeycode::Return => Key::Enter,
+        SdlKeycode::Tab => Key::Tab,
+        SdlKeycode::Space => Key::Space,
         SdlKeycode::Backspace => Key::Backspace,
+        SdlKeycode::CapsLock => Key::CapsLock,
+        SdlKeycode::ScrollLock => Key::ScrollLock,
+        SdlKeycode::NumLockClear => Key::NumLock,
+        SdlKeycode::PrintScreen => Key::PrintScreen,
+        SdlKeycode::Pause => Key::Pause,
+        SdlKeycode::Application => Key::Menu,
+        SdlKeycode::Kp0 => Key::Kp0,
+        SdlKeycode::Kp1 => Key::Kp1,
+        SdlKeycode::Kp2 => Key::Kp2,
+        SdlKeycode::Kp3 => Key::Kp3,
+        SdlKeycode::Kp4 => Key::Kp4,
+        SdlKeycode::Kp5 => Key::Kp5,
+        SdlKeycode::Kp6 => Key::Kp6,
+        SdlKeycode::Kp7 => Key::Kp7,
+        SdlKeycode::Kp8 => Key::Kp8,
+        SdlKeycode::Kp9 => Key::Kp9,
+        SdlKeycode::KpDivide => Key::KpDivide,
+        SdlKeycode::KpMultiply => Key::KpMultiply,
+        SdlKeycode::KpMinus => Key::KpMinus,
+        SdlKeycode::KpPlus => Key::KpPlus,
+        SdlKeycode::KpEnter => Key::KpEnter,
+        SdlKeycode::KpPeriod => Key::KpPeriod,
+        SdlKeycode::KpEquals => Key::KpEquals,
+        SdlKeycode::Minus => Key::Minus,
+        SdlKeycode::Equals => Key::Equals,
+        SdlKeycode::LeftBracket => Key::LeftBracket,
+        SdlKeycode::RightBracket => Key::RightBracket,
+        SdlKeycode::Backslash => Key::Backslash,
+        SdlKeycode::Semicolon => Key::Semicolon,
+        SdlKeycode::Quote => Key::Apostrophe,
+        SdlKeycode::Backquote => Key::Grave,
+        SdlKeycode::Comma => Key::Comma,
+        SdlKeycode::Period => Key::Period,
+        SdlKeycode::Slash => Key::Slash,
+        SdlKeycode::LShift => Key::LShift,
+        SdlKeycode::RShift => Key::RShift,
+        SdlKeycode::LCtrl => Key::LCtrl,
+        SdlKeycode::RCtrl => Key::RCtrl,
+        SdlKeycode::LAlt => Key::LAlt,
+        SdlKeycode::RAlt => Key::RAlt,
+        SdlKeycode::LGui => Key::LGui,
+        SdlKeycode::RGui => Key::RGui,
+        _ => Key::Unknown,
+    }
+}
+
+fn convert_scancode(scancode: SdlScancode) -> Key {
+    match scancode {
+        SdlScancode::A => Key::A,
+        SdlScancode::B => Key::B,
+        SdlScancode::C => Key::C,
+        SdlScancode::D => Key::D,
+        SdlScancode::E => Key::E,
+        SdlScancode::F => Key::F,
+        SdlScancode::G => Key::G,
+        SdlScancode::H => Key::H,
+        SdlScancode::I => Key::I,
+        SdlScancode::J => Key::J,
+        SdlScancode::K => Key::K,
+        SdlScancode::L => Key::L,
+        SdlScancode::M => Key::M,
+        SdlScancode::N => Key::N,
+        SdlScancode::O => Key::O,
+        SdlScancode::P => Key::P,
+        SdlScancode::Q => Key::Q,
+        SdlScancode::R => Key::R,
+        SdlScancode::S => Key::S,
+        SdlScancode::T => Key::T,
+        SdlScancode::U => Key::U,
+        SdlScancode::V => Key::V,
+        SdlScancode::W => Key::W,
+        SdlScancode::X => Key::X,
+        SdlScancode::Y => Key::Y,
+        SdlScancode::Z => Key::Z,
+        SdlScancode::Num0 => Key::Num0,
+        SdlScancode::Num1 => Key::Num1,
+        SdlScancode::Num2 => Key::Num2,
+        SdlScancode::Num3 => Key::Num3,
+        SdlScancode::Num4 => Key::Num4,
+        SdlScancode::Num5 => Key::Num5,
+        SdlScancode::Num6 => Key::Num6,
+        SdlScancode::Num7 => Key::Num7,
+        SdlScancode::Num8 => Key::Num8,
+        SdlScancode::Num9 => Key::Num9,
+        SdlScancode::F1 => Key::F1,
+        SdlScancode::F2 => Key::F2,
+        SdlScancode::F3 => Key::F3,
+        SdlScancode::F4 => Key::F4,
+        SdlScancode::F5 => Key::F5,
+        SdlScancode::F6 => Key::F6,
+        SdlScancode::F7 => Key::F7,
+        SdlScancode::F8 => Key::F8,
+        SdlScancode::F9 => Key::F9,
+        SdlScancode::F10 => Key::F10,
+        SdlScancode::F11 => Key::F11,
+        SdlScancode::F12 => Key::F12,
+        SdlScancode::Up => Key::Up,
+        SdlScancode::Down => Key::Down,
+        SdlScancode::Left => Key::Left,
+        SdlScancode::Right => Key::Right,
+        SdlScancode::Insert => Key::Insert,
+        SdlScancode::Delete => Key::Delete,
+        SdlScancode::Home => Key::Home,
+        SdlScancode::End => Key::End,
+        SdlScancode::PageUp => Key::PageUp,
+        SdlScancode::PageDown => Key::PageDown,
+        SdlScancode::Escape => Key::Escape,
+        SdlScancode::Return => Key::Enter,
+        SdlScancode::Tab => Key::Tab,
+        SdlScancode::Space => Key::Space,
+        SdlScancode::Backspace => Key::Backspace,
+        SdlScancode::CapsLock => Key::CapsLock,
+        SdlScancode::ScrollLock => Key::ScrollLock,
+        SdlScancode::NumLockClear => Key::NumLock,
+        SdlScancode::PrintScreen => Key::PrintScreen,
+        SdlScancode::Pause => Key::Pause,
+        SdlScancode::Application => Key::Menu,
+        SdlScancode::Kp0 => Key::Kp0,
+        SdlScancode::Kp1 => Key::Kp1,
+        SdlScancode::Kp2 => Key::Kp2,
+        SdlScancode::Kp3 => Key::Kp3,
+        SdlScancode::Kp4 => Key::Kp4,
+        SdlScancode::Kp5 => Key::Kp5,
+        SdlScancode::Kp6 => Key::Kp6,
+        SdlScancode::Kp7 => Key::Kp7,
+        SdlScancode::Kp8 => Key::Kp8,
+        SdlScancode::Kp9 => Key::Kp9,
+        SdlScancode::KpDivide => Key::KpDivide,
+        SdlScancode::KpMultiply => Key::KpMultiply,
+        SdlScancode::KpMinus => Key::KpMinus,
+        SdlScancode::KpPlus => Key::KpPlus,
+        SdlScancode::KpEnter => Key::KpEnter,
+        SdlScancode::KpPeriod => Key::KpPeriod,
+        SdlScancode::KpEquals => Key::KpEquals,
+        SdlScancode::Minus => Key::Minus,
+        SdlScancode::Equals => Key::Equals,
+        SdlScancode::LeftBracket => Key::LeftBracket,
+        SdlScancode::RightBracket => Key::RightBracket,
+        SdlScancode::Backslash => Key::Backslash,
+        SdlScancode::Semicolon => Key::Semicolon,
+        SdlScancode::Apostrophe => Key::Apostrophe,
+        SdlScancode::Grave => Key::Grave,
+        SdlScancode::Comma => Key::Comma,
+        SdlScancode::Period => Key::Period,
+        SdlScancode::Slash => Key::Slash,
+        SdlScancode::LShift => Key::LShift,
+        SdlScancode::RShift => Key::RShift,
+        SdlScancode::LCtrl => Key::LCtrl,
+        SdlScancode::RCtrl => Key::RCtrl,
+        SdlScancode::LAlt => Key::LAlt,
+        SdlScancode::RAlt => Key::RAlt,
+        SdlScancode::LGui => Key::LGui,
+        SdlScancode::RGui => Key::RGui,
         _ => Key::Unknown,
     }
 }
@@ -8,7 +8,7 @@ use cherry::{
 struct Game;
 
 impl Cherry for Game {
-    fn on_update(&mut self, engine: &mut cherry::engine::Engine) {
+    fn on_update(&mut self, engine: &mut cherry::engine::Engine, _dt: f32) {
         engine.set_fg(Colour::WHITE);
         engine.set_bg(Colour::BLACK);
         engine.clear();